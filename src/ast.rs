@@ -1,4 +1,3 @@
-use crate::tokens::Identifier;
 use anyhow::{anyhow, Error, Result};
 
 #[derive(Debug, PartialEq, Clone)]
@@ -21,80 +20,58 @@ impl Ast {
     pub fn err<T>(&self, message: &str) -> Result<T> {
         Err(self.error(message))
     }
+}
 
-    pub fn as_int(&self) -> Result<i64> {
-        if let AstKind::Int(value) = &self.kind {
-            Ok(*value)
-        } else {
-            self.err("Expected integer")
-        }
-    }
-
-    pub fn as_float(&self) -> Result<f64> {
-        if let AstKind::Float(value) = &self.kind {
-            Ok(*value)
-        } else {
-            self.err("Expected float")
-        }
-    }
-
-    pub fn as_bool(&self) -> Result<bool> {
-        if let AstKind::Bool(value) = &self.kind {
-            Ok(*value)
-        } else {
-            self.err("Expected boolean")
-        }
-    }
-
-    pub fn as_char(&self) -> Result<char> {
-        if let AstKind::Char(value) = &self.kind {
-            Ok(*value)
-        } else {
-            self.err("Expected char")
-        }
-    }
-
-    pub fn as_string(&self) -> Result<String> {
-        if let AstKind::String(value) = &self.kind {
-            Ok(value.clone())
-        } else {
-            self.err("Expected string")
-        }
-    }
-
-    pub fn as_array(&self) -> Result<Vec<Ast>> {
-        if let AstKind::Array(value) = &self.kind {
-            Ok(value.clone())
-        } else {
-            self.err("Expected array")
-        }
-    }
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Lt,
+    Le,
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+}
 
-    pub fn as_identifier(&self) -> Result<Identifier> {
-        if let AstKind::Identifier(identifier) = &self.kind {
-            Ok(identifier.clone())
-        } else {
-            self.err("Expected identifier")
+impl BinaryOp {
+    /// Higher binds tighter; comparisons bind loosest so `a + b < c` parses
+    /// as `(a + b) < c`.
+    pub fn precedence(&self) -> usize {
+        match self {
+            BinaryOp::Mul | BinaryOp::Div => 2,
+            BinaryOp::Add | BinaryOp::Sub => 1,
+            BinaryOp::Lt
+            | BinaryOp::Le
+            | BinaryOp::Eq
+            | BinaryOp::Ne
+            | BinaryOp::Gt
+            | BinaryOp::Ge => 0,
         }
     }
 
-    pub fn as_call(&self) -> Result<(Identifier, Vec<Ast>)> {
-        if let AstKind::Call { name, args } = &self.kind {
-            Ok((name.clone(), args.clone()))
-        } else {
-            self.err("Expected call")
-        }
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            BinaryOp::Lt | BinaryOp::Le | BinaryOp::Eq | BinaryOp::Ne | BinaryOp::Gt | BinaryOp::Ge
+        )
     }
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum AstKind {
-    Int(i64),
-    Float(f64),
-    Bool(bool),
-    Char(char),
-    String(String),
-    Array(Vec<Ast>),
-    Identifier(Identifier),
-    Call { name: Identifier, args: Vec<Ast> },
+    Number(f64),
+    Input,
+    BinaryOp {
+        op: BinaryOp,
+        lhs: Box<Ast>,
+        rhs: Box<Ast>,
+    },
+    If {
+        cond: Box<Ast>,
+        then: Box<Ast>,
+        else_: Box<Ast>,
+    },
 }
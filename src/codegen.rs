@@ -4,8 +4,8 @@ use inkwell::builder::{Builder, BuilderError};
 use inkwell::context::Context;
 use inkwell::execution_engine::JitFunction;
 use inkwell::module::Module;
-use inkwell::values::FloatValue;
-use inkwell::{AddressSpace, OptimizationLevel};
+use inkwell::values::{FloatValue, IntValue};
+use inkwell::{AddressSpace, FloatPredicate, OptimizationLevel};
 
 /// Convenience type alias for the `ast` function.
 ///
@@ -19,6 +19,24 @@ struct CodeGen<'ctx> {
     builder: Builder<'ctx>,
 }
 
+/// A compiled expression's value: either the language's one numeric type,
+/// or the `i1` a comparison produces. `compile_ast_node` returns this
+/// instead of a bare `FloatValue` now that comparisons and `if` exist.
+#[derive(Clone, Copy)]
+enum Value<'ctx> {
+    Float(FloatValue<'ctx>),
+    Bool(IntValue<'ctx>),
+}
+
+impl<'ctx> Value<'ctx> {
+    fn as_float(self) -> Result<FloatValue<'ctx>, BuilderError> {
+        match self {
+            Value::Float(f) => Ok(f),
+            Value::Bool(_) => Err(BuilderError::GEPIndex),
+        }
+    }
+}
+
 impl<'ctx> CodeGen<'ctx> {
     fn jit_compile_ast(&self, ast: &Ast) -> Option<JitFunction<AstFunc>> {
         let execution_engine = self
@@ -41,7 +59,7 @@ impl<'ctx> CodeGen<'ctx> {
 
         self.builder.position_at_end(basic_block);
 
-        let result = self.compile_ast_node(ast)?;
+        let result = self.compile_ast_node(ast)?.as_float()?;
 
         self.builder.build_return(Some(&result))?;
 
@@ -74,9 +92,9 @@ impl<'ctx> CodeGen<'ctx> {
         Ok(())
     }
 
-    fn compile_ast_node(&self, ast: &Ast) -> Result<FloatValue, BuilderError> {
+    fn compile_ast_node(&self, ast: &Ast) -> Result<Value<'ctx>, BuilderError> {
         match &ast.kind {
-            AstKind::Number(n) => Ok(self.context.f64_type().const_float(*n)),
+            AstKind::Number(n) => Ok(Value::Float(self.context.f64_type().const_float(*n))),
             AstKind::Input => {
                 let f64_type = self.context.f64_type();
 
@@ -95,17 +113,87 @@ impl<'ctx> CodeGen<'ctx> {
                 )?;
 
                 let input_value = self.builder.build_load(input_ptr, "input_val")?;
-                Ok(input_value.into_float_value())
+                Ok(Value::Float(input_value.into_float_value()))
             }
             AstKind::BinaryOp { op, lhs, rhs } => {
-                let lhs = self.compile_ast_node(lhs)?;
-                let rhs = self.compile_ast_node(rhs)?;
+                let lhs = self.compile_ast_node(lhs)?.as_float()?;
+                let rhs = self.compile_ast_node(rhs)?.as_float()?;
+
+                if op.is_comparison() {
+                    let predicate = match op {
+                        BinaryOp::Lt => FloatPredicate::OLT,
+                        BinaryOp::Le => FloatPredicate::OLE,
+                        BinaryOp::Eq => FloatPredicate::OEQ,
+                        BinaryOp::Ne => FloatPredicate::ONE,
+                        BinaryOp::Gt => FloatPredicate::OGT,
+                        BinaryOp::Ge => FloatPredicate::OGE,
+                        _ => unreachable!("is_comparison() already filtered to comparison ops"),
+                    };
+
+                    return Ok(Value::Bool(self.builder.build_float_compare(
+                        predicate, lhs, rhs, "cmptmp",
+                    )?));
+                }
 
-                match op {
+                let result = match op {
                     BinaryOp::Add => self.builder.build_float_add(lhs, rhs, "addtmp"),
                     BinaryOp::Sub => self.builder.build_float_sub(lhs, rhs, "subtmp"),
                     BinaryOp::Mul => self.builder.build_float_mul(lhs, rhs, "multmp"),
                     BinaryOp::Div => self.builder.build_float_div(lhs, rhs, "divtmp"),
+                    _ => unreachable!("comparison ops were already handled above"),
+                }?;
+
+                Ok(Value::Float(result))
+            }
+            AstKind::If { cond, then, else_ } => {
+                let cond = self.compile_ast_node(cond)?;
+                let cond = match cond {
+                    Value::Bool(b) => b,
+                    Value::Float(f) => self.builder.build_float_compare(
+                        FloatPredicate::ONE,
+                        f,
+                        self.context.f64_type().const_zero(),
+                        "ifcond",
+                    )?,
+                };
+
+                let function = self
+                    .builder
+                    .get_insert_block()
+                    .and_then(|b| b.get_parent())
+                    .ok_or(BuilderError::GEPIndex)?;
+
+                let then_block = self.context.append_basic_block(function, "then");
+                let else_block = self.context.append_basic_block(function, "else");
+                let merge_block = self.context.append_basic_block(function, "ifcont");
+
+                self.builder
+                    .build_conditional_branch(cond, then_block, else_block)?;
+
+                self.builder.position_at_end(then_block);
+                let then_value = self.compile_ast_node(then)?;
+                self.builder.build_unconditional_branch(merge_block)?;
+                let then_block = self.builder.get_insert_block().ok_or(BuilderError::GEPIndex)?;
+
+                self.builder.position_at_end(else_block);
+                let else_value = self.compile_ast_node(else_)?;
+                self.builder.build_unconditional_branch(merge_block)?;
+                let else_block = self.builder.get_insert_block().ok_or(BuilderError::GEPIndex)?;
+
+                self.builder.position_at_end(merge_block);
+
+                match (then_value, else_value) {
+                    (Value::Float(then_value), Value::Float(else_value)) => {
+                        let phi = self.builder.build_phi(self.context.f64_type(), "iftmp")?;
+                        phi.add_incoming(&[(&then_value, then_block), (&else_value, else_block)]);
+                        Ok(Value::Float(phi.as_basic_value().into_float_value()))
+                    }
+                    (Value::Bool(then_value), Value::Bool(else_value)) => {
+                        let phi = self.builder.build_phi(self.context.bool_type(), "iftmp")?;
+                        phi.add_incoming(&[(&then_value, then_block), (&else_value, else_block)]);
+                        Ok(Value::Bool(phi.as_basic_value().into_int_value()))
+                    }
+                    _ => Err(BuilderError::GEPIndex),
                 }
             }
         }
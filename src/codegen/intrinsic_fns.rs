@@ -1,192 +1,255 @@
 use anyhow::{anyhow, Result};
-use inkwell::{
-    values::{AnyValue, AnyValueEnum, AsValueRef, PointerValue},
-    AddressSpace,
+use inkwell::values::{AnyValue, AnyValueEnum};
+
+use crate::{
+    diagnostics::{Diagnostic, Span},
+    interpreter::value::ItpTypeValue,
 };
 
 use super::CodeGen;
 
+/// Whether `ty` is a signed integer type. Only meaningful once the caller
+/// has already confirmed the operand it came from lowered to an `IntValue`;
+/// LLVM ints are themselves signless, so this is what picks between a
+/// signed and unsigned `IntPredicate`/division.
+fn is_signed(ty: &ItpTypeValue) -> bool {
+    matches!(ty, ItpTypeValue::Int(width) if width.signed)
+}
+
+/// Builds an intrinsic error the same way the interpreter builds one for a
+/// recoverable problem: an annotated snippet of `codegen.source` with a
+/// caret under the call site, rather than a bare message with no view of
+/// where in the user's program it came from.
+pub(crate) fn intrinsic_error(
+    codegen: &CodeGen,
+    line: usize,
+    column: usize,
+    message: impl Into<String>,
+) -> anyhow::Error {
+    anyhow!(
+        "{}",
+        Diagnostic::error(message, Span::new(line, column)).render(codegen.source)
+    )
+}
+
 pub(crate) fn check_intrinsic_fn<'a>(
     name: &str,
     codegen: &CodeGen<'a>,
     params: &[AnyValueEnum<'a>],
+    arg_types: &[ItpTypeValue],
+    result_type: &ItpTypeValue,
+    line: usize,
+    column: usize,
 ) -> Result<Option<AnyValueEnum<'a>>> {
     match name {
-        "+" => {
-            let lhs = params
-                .get(0)
-                .ok_or_else(|| anyhow!("Expected first parameter for '+' function"))?;
-            let rhs = params
-                .get(1)
-                .ok_or_else(|| anyhow!("Expected second parameter for '+' function"))?;
-
-            let AnyValueEnum::FloatValue(lhs) = lhs else {
-                return Err(anyhow!(
-                    "Expected float for first parameter of '+' function"
-                ));
-            };
-            let AnyValueEnum::FloatValue(rhs) = rhs else {
-                return Err(anyhow!(
-                    "Expected float for second parameter of '+' function"
-                ));
-            };
-
-            let result = codegen.builder.build_float_add(*lhs, *rhs, "addtmp");
-
-            match result {
-                Ok(result) => Ok(Some(AnyValueEnum::FloatValue(result))),
-                Err(err) => Err(anyhow!(err)),
-            }
+        "some" => {
+            let value = params.get(0).ok_or_else(|| {
+                intrinsic_error(
+                    codegen,
+                    line,
+                    column,
+                    "Expected value parameter for 'some' function",
+                )
+            })?;
+            let payload = super::try_as_basic_value_enum(*value)?;
+
+            let struct_ty = codegen
+                .context
+                .struct_type(&[codegen.context.bool_type().into(), payload.get_type()], false);
+            let alloca = codegen.builder.build_alloca(struct_ty, "opt")?;
+
+            let present_ptr = codegen.builder.build_struct_gep(alloca, 0, "present")?;
+            codegen
+                .builder
+                .build_store(present_ptr, codegen.context.bool_type().const_int(1, false))?;
+            let payload_ptr = codegen.builder.build_struct_gep(alloca, 1, "payload")?;
+            codegen.builder.build_store(payload_ptr, payload)?;
+
+            let loaded = codegen.builder.build_load(alloca, "opt_val")?;
+            Ok(Some(loaded.as_any_value_enum()))
         }
-        "-" => {
-            let lhs = params
-                .get(0)
-                .ok_or_else(|| anyhow!("Expected first parameter for '-' function"))?;
-            let rhs = params
-                .get(1)
-                .ok_or_else(|| anyhow!("Expected second parameter for '-' function"))?;
-
-            let AnyValueEnum::FloatValue(lhs) = lhs else {
-                return Err(anyhow!(
-                    "Expected float for first parameter of '-' function"
-                ));
-            };
-            let AnyValueEnum::FloatValue(rhs) = rhs else {
-                return Err(anyhow!(
-                    "Expected float for second parameter of '-' function"
-                ));
+        "none" => {
+            let struct_ty = match codegen.type_of(result_type) {
+                inkwell::types::AnyTypeEnum::StructType(t) => t,
+                _ => {
+                    return Err(intrinsic_error(
+                        codegen,
+                        line,
+                        column,
+                        "'none' must resolve to an Option type",
+                    ))
+                }
             };
-
-            let result = codegen.builder.build_float_sub(*lhs, *rhs, "subtmp");
-
-            match result {
-                Ok(result) => Ok(Some(AnyValueEnum::FloatValue(result))),
-                Err(err) => Err(anyhow!(err)),
-            }
+            Ok(Some(struct_ty.const_zero().as_any_value_enum()))
         }
-        "*" => {
-            let lhs = params
-                .get(0)
-                .ok_or_else(|| anyhow!("Expected first parameter for '*' function"))?;
-            let rhs = params
-                .get(1)
-                .ok_or_else(|| anyhow!("Expected second parameter for '*' function"))?;
-
-            let AnyValueEnum::FloatValue(lhs) = lhs else {
-                return Err(anyhow!(
-                    "Expected float for first parameter of '*' function"
-                ));
-            };
-            let AnyValueEnum::FloatValue(rhs) = rhs else {
-                return Err(anyhow!(
-                    "Expected float for second parameter of '*' function"
+        "unwrap" => {
+            let option = params.get(0).ok_or_else(|| {
+                intrinsic_error(
+                    codegen,
+                    line,
+                    column,
+                    "Expected option parameter for 'unwrap' function",
+                )
+            })?;
+            let AnyValueEnum::StructValue(option) = option else {
+                return Err(intrinsic_error(
+                    codegen,
+                    line,
+                    column,
+                    "Expected an Option struct for 'unwrap'",
                 ));
             };
 
-            let result = codegen.builder.build_float_mul(*lhs, *rhs, "multmp");
-
-            match result {
-                Ok(result) => Ok(Some(AnyValueEnum::FloatValue(result))),
-                Err(err) => Err(anyhow!(err)),
-            }
+            let present = codegen
+                .builder
+                .build_extract_value(*option, 0, "present")?
+                .into_int_value();
+            let payload = codegen.builder.build_extract_value(*option, 1, "payload")?;
+
+            let function = codegen
+                .builder
+                .get_insert_block()
+                .and_then(|b| b.get_parent())
+                .ok_or_else(|| {
+                    intrinsic_error(codegen, line, column, "'unwrap' used outside a function")
+                })?;
+            let panic_block = codegen.context.append_basic_block(function, "unwrap_none");
+            let ok_block = codegen.context.append_basic_block(function, "unwrap_ok");
+            codegen
+                .builder
+                .build_conditional_branch(present, ok_block, panic_block)?;
+
+            codegen.builder.position_at_end(panic_block);
+            codegen.emit_panic("called `unwrap` on a `none` value")?;
+
+            codegen.builder.position_at_end(ok_block);
+            Ok(Some(payload.as_any_value_enum()))
         }
-        "/" => {
-            let lhs = params
-                .get(0)
-                .ok_or_else(|| anyhow!("Expected first parameter for '/' function"))?;
-            let rhs = params
-                .get(1)
-                .ok_or_else(|| anyhow!("Expected second parameter for '/' function"))?;
-
-            let AnyValueEnum::FloatValue(lhs) = lhs else {
-                return Err(anyhow!(
-                    "Expected float for first parameter of '/' function"
-                ));
-            };
-            let AnyValueEnum::FloatValue(rhs) = rhs else {
-                return Err(anyhow!(
-                    "Expected float for second parameter of '/' function"
-                ));
-            };
-
-            let result = codegen.builder.build_float_div(*lhs, *rhs, "divtmp");
-
-            match result {
-                Ok(result) => Ok(Some(AnyValueEnum::FloatValue(result))),
-                Err(err) => Err(anyhow!(err)),
-            }
-        }
-        "==" => {
-            let lhs = params
-                .get(0)
-                .ok_or_else(|| anyhow!("Expected first parameter for '==' function"))?;
-            let rhs = params
-                .get(1)
-                .ok_or_else(|| anyhow!("Expected second parameter for '==' function"))?;
-
-            let AnyValueEnum::FloatValue(lhs) = lhs else {
-                return Err(anyhow!(
-                    "Expected float for first parameter of '==' function"
-                ));
-            };
-            let AnyValueEnum::FloatValue(rhs) = rhs else {
-                return Err(anyhow!(
-                    "Expected float for second parameter of '==' function"
-                ));
-            };
-
-            let result = codegen.builder.build_float_compare(
-                inkwell::FloatPredicate::OEQ,
-                *lhs,
-                *rhs,
-                "eqtmp",
-            );
-
-            match result {
-                Ok(result) => Ok(Some(AnyValueEnum::IntValue(result))),
-                Err(err) => Err(anyhow!(err)),
+        "+" | "-" | "*" | "/" => {
+            let lhs = params.get(0).ok_or_else(|| {
+                intrinsic_error(
+                    codegen,
+                    line,
+                    column,
+                    format!("Expected first parameter for '{}' function", name),
+                )
+            })?;
+            let rhs = params.get(1).ok_or_else(|| {
+                intrinsic_error(
+                    codegen,
+                    line,
+                    column,
+                    format!("Expected second parameter for '{}' function", name),
+                )
+            })?;
+
+            match (lhs, rhs) {
+                (AnyValueEnum::FloatValue(lhs), AnyValueEnum::FloatValue(rhs)) => {
+                    let result = match name {
+                        "+" => codegen.builder.build_float_add(*lhs, *rhs, "addtmp"),
+                        "-" => codegen.builder.build_float_sub(*lhs, *rhs, "subtmp"),
+                        "*" => codegen.builder.build_float_mul(*lhs, *rhs, "multmp"),
+                        "/" => codegen.builder.build_float_div(*lhs, *rhs, "divtmp"),
+                        _ => unreachable!(),
+                    };
+                    Ok(Some(result.map_err(|err| anyhow!(err))?.as_any_value_enum()))
+                }
+                (AnyValueEnum::IntValue(lhs), AnyValueEnum::IntValue(rhs)) => {
+                    let result = match name {
+                        "+" => codegen.builder.build_int_add(*lhs, *rhs, "addtmp"),
+                        "-" => codegen.builder.build_int_sub(*lhs, *rhs, "subtmp"),
+                        "*" => codegen.builder.build_int_mul(*lhs, *rhs, "multmp"),
+                        "/" if is_signed(&arg_types[0]) => {
+                            codegen.builder.build_int_signed_div(*lhs, *rhs, "divtmp")
+                        }
+                        "/" => codegen.builder.build_int_unsigned_div(*lhs, *rhs, "divtmp"),
+                        _ => unreachable!(),
+                    };
+                    Ok(Some(result.map_err(|err| anyhow!(err))?.as_any_value_enum()))
+                }
+                _ => Err(intrinsic_error(
+                    codegen,
+                    line,
+                    column,
+                    format!(
+                        "'{}' requires two operands of the same kind (both float or both int)",
+                        name
+                    ),
+                )),
             }
         }
-        "get" => {
-            let array = params
-                .get(0)
-                .ok_or_else(|| anyhow!("Expected first parameter for 'get' function"))?;
-            let index = params
-                .get(1)
-                .ok_or_else(|| anyhow!("Expected second parameter for 'get' function"))?;
-
-            match array {
-                AnyValueEnum::PointerValue(array) => {
-                    let index = match index {
-                        AnyValueEnum::FloatValue(index) => {
-                            codegen.builder.build_float_to_unsigned_int(
-                                *index,
-                                codegen.context.i64_type(),
-                                "index",
-                            )?
-                        }
-                        AnyValueEnum::IntValue(index) => *index,
-                        _ => return Err(anyhow!("Expected number for index of 'get' function")),
+        "==" | "!=" | "<" | ">" | "<=" | ">=" => {
+            let lhs = params.get(0).ok_or_else(|| {
+                intrinsic_error(
+                    codegen,
+                    line,
+                    column,
+                    format!("Expected first parameter for '{}' function", name),
+                )
+            })?;
+            let rhs = params.get(1).ok_or_else(|| {
+                intrinsic_error(
+                    codegen,
+                    line,
+                    column,
+                    format!("Expected second parameter for '{}' function", name),
+                )
+            })?;
+
+            match (lhs, rhs) {
+                (AnyValueEnum::FloatValue(lhs), AnyValueEnum::FloatValue(rhs)) => {
+                    let predicate = match name {
+                        "==" => inkwell::FloatPredicate::OEQ,
+                        "!=" => inkwell::FloatPredicate::ONE,
+                        "<" => inkwell::FloatPredicate::OLT,
+                        ">" => inkwell::FloatPredicate::OGT,
+                        "<=" => inkwell::FloatPredicate::OLE,
+                        ">=" => inkwell::FloatPredicate::OGE,
+                        _ => unreachable!(),
                     };
-
-                    let result = unsafe {
-                        codegen.builder.build_gep(
-                            *array,
-                            &[codegen.context.i64_type().const_zero(), index],
-                            "elementptr",
-                        )
-                    }?;
-
-                    let result = codegen.builder.build_load(result, "element")?;
-
-                    Ok(Some(result.as_any_value_enum()))
+                    let result = codegen
+                        .builder
+                        .build_float_compare(predicate, *lhs, *rhs, "cmptmp")
+                        .map_err(|err| anyhow!(err))?;
+                    Ok(Some(AnyValueEnum::IntValue(result)))
+                }
+                (AnyValueEnum::IntValue(lhs), AnyValueEnum::IntValue(rhs)) => {
+                    let signed = is_signed(&arg_types[0]);
+                    let predicate = match name {
+                        "==" => inkwell::IntPredicate::EQ,
+                        "!=" => inkwell::IntPredicate::NE,
+                        "<" if signed => inkwell::IntPredicate::SLT,
+                        "<" => inkwell::IntPredicate::ULT,
+                        ">" if signed => inkwell::IntPredicate::SGT,
+                        ">" => inkwell::IntPredicate::UGT,
+                        "<=" if signed => inkwell::IntPredicate::SLE,
+                        "<=" => inkwell::IntPredicate::ULE,
+                        ">=" if signed => inkwell::IntPredicate::SGE,
+                        ">=" => inkwell::IntPredicate::UGE,
+                        _ => unreachable!(),
+                    };
+                    let result = codegen
+                        .builder
+                        .build_int_compare(predicate, *lhs, *rhs, "cmptmp")
+                        .map_err(|err| anyhow!(err))?;
+                    Ok(Some(AnyValueEnum::IntValue(result)))
                 }
-                a => Err(anyhow!(
-                    "Expected pointer for first parameter of 'get' function. Got {:?}",
-                    a
+                _ => Err(intrinsic_error(
+                    codegen,
+                    line,
+                    column,
+                    format!(
+                        "'{}' requires two operands of the same kind (both float or both int)",
+                        name
+                    ),
                 )),
             }
         }
+        // `get` is handled before it ever reaches `call()`: `interpret_ast`
+        // turns a `(get array index)` call into an `Index` node so the
+        // element type and bounds check can be resolved precisely (see
+        // `Interpreter::build_index` and the `ItpAstKind::Index` codegen
+        // arm), rather than treating it as an opaque intrinsic here.
         _ => Ok(None),
     }
 }
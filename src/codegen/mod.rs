@@ -23,10 +23,14 @@ use inkwell::{
     values::AnyValue,
 };
 use inkwell::{AddressSpace, OptimizationLevel};
+use inkwell::passes::PassManager;
+use inkwell::targets::{FileType, InitializationConfig, Target, TargetMachine};
 
 use self::intrinsic_fns::check_intrinsic_fn;
+pub use self::options::CompileOptions;
 
 mod intrinsic_fns;
+mod options;
 
 /// Convenience type alias for the `main` function.
 ///
@@ -75,6 +79,35 @@ fn try_as_basic_type_enum<'a>(ty: AnyTypeEnum<'a>) -> Result<BasicTypeEnum<'a>>
     }
 }
 
+/// Resolves `field`'s position within `ty`'s declared fields, for the
+/// `build_struct_gep` index `Field`/`SetField` need at codegen time.
+fn struct_field_index(ty: &ItpTypeValue, field: &str) -> Result<u32> {
+    match ty {
+        ItpTypeValue::Struct { fields, .. } => fields
+            .iter()
+            .position(|(name, _)| name == field)
+            .map(|i| i as u32)
+            .ok_or_else(|| anyhow!("No field named {} on struct", field)),
+        other => Err(anyhow!("Expected a struct value, got {:?}", other)),
+    }
+}
+
+/// Whether control flow can never fall through past `ast` - it always
+/// branches away instead of producing a value the enclosing block can use.
+/// `Conditional` diverges iff both arms do; a `Call` diverges iff it's
+/// calling a function whose declared return type is `Never` (the bottom
+/// type a `noreturn` function is given, see `ItpTypeValue::Never`).
+/// Consulted before emitting the merge-block `br` for an if/else, so a
+/// branch that already diverged doesn't get a spurious second terminator
+/// appended (which LLVM rejects).
+fn diverges(ast: &ItpAst) -> bool {
+    match &ast.kind {
+        ItpAstKind::Conditional { then, else_, .. } => diverges(then) && diverges(else_),
+        ItpAstKind::Call { result, .. } => *result == ItpTypeValue::Never,
+        _ => false,
+    }
+}
+
 fn try_as_basic_value_enum<'a>(value: AnyValueEnum<'a>) -> Result<BasicValueEnum<'a>> {
     match value {
         AnyValueEnum::ArrayValue(a) => Ok(a.as_basic_value_enum()),
@@ -93,10 +126,15 @@ pub struct CodeGen<'t> {
     module: Module<'t>,
     builder: Builder<'t>,
     variables: RefCell<HashMap<String, PointerValue<'t>>>,
+    /// The program text, kept around so an intrinsic error can render an
+    /// annotated snippet the same way `Diagnostic::render` does for
+    /// interpret-time errors (see `intrinsic_fns::intrinsic_error`).
+    source: &'t str,
+    options: CompileOptions,
 }
 
 impl<'t> CodeGen<'t> {
-    pub fn new(context: &'t Context) -> Self {
+    pub fn new(context: &'t Context, source: &'t str, options: CompileOptions) -> Self {
         let module = context.create_module("main");
         let builder = context.create_builder();
 
@@ -105,7 +143,56 @@ impl<'t> CodeGen<'t> {
             module,
             builder,
             variables: RefCell::new(HashMap::new()),
+            source,
+            options,
+        }
+    }
+
+    /// Builds the `TargetMachine` described by `self.options`, initializing
+    /// every backend inkwell was built with along the way, so a non-host
+    /// `target_triple` resolves as well as the host's. Used by
+    /// `compile_to_object_file`'s AOT emission.
+    fn target_machine(&self) -> Result<TargetMachine> {
+        Target::initialize_all(&InitializationConfig::default());
+
+        let triple = match &self.options.target_triple {
+            Some(triple) => inkwell::targets::TargetTriple::create(triple),
+            None => TargetMachine::get_default_triple(),
+        };
+        let target = Target::from_triple(&triple).map_err(|err| anyhow!(err))?;
+
+        target
+            .create_target_machine(
+                &triple,
+                "generic",
+                "",
+                self.options.opt_level,
+                self.options.reloc_mode,
+                self.options.code_model,
+            )
+            .ok_or_else(|| anyhow!("Could not create a target machine for {}", triple))
+    }
+
+    /// Runs the classic mem2reg/instcombine/reassociate/gvn/simplifycfg
+    /// pipeline over the module at any `opt_level` above `None`, with
+    /// `Aggressive` additionally running the inliner. A no-op otherwise, so
+    /// the default `CompileOptions` keeps emitting exactly the unoptimized
+    /// IR callers saw before this pipeline existed.
+    fn run_optimization_passes(&self) {
+        if self.options.opt_level == OptimizationLevel::None {
+            return;
+        }
+
+        let pass_manager = PassManager::create(());
+        pass_manager.add_promote_memory_to_register_pass();
+        pass_manager.add_instruction_combining_pass();
+        pass_manager.add_reassociate_pass();
+        pass_manager.add_gvn_pass();
+        pass_manager.add_cfg_simplification_pass();
+        if self.options.opt_level == OptimizationLevel::Aggressive {
+            pass_manager.add_function_inlining_pass();
         }
+        pass_manager.run_on(&self.module);
     }
 
     // fn declare_printf(&self) -> Result<(), BuilderError> {
@@ -123,7 +210,7 @@ impl<'t> CodeGen<'t> {
     fn jit_compile(&'t self, itp: &Interpreter) -> Result<JitFunction<MainFunc>> {
         let execution_engine = self
             .module
-            .create_jit_execution_engine(OptimizationLevel::None)
+            .create_jit_execution_engine(self.options.opt_level)
             .map_err(|err| anyhow!(format!("{}", err)))?;
 
         self.compile(itp).map_err(|err| anyhow!(err))?;
@@ -138,6 +225,7 @@ impl<'t> CodeGen<'t> {
     fn compile(&'t self, itp: &Interpreter) -> Result<()> {
         self.declare_functions(itp)?;
         self.compile_functions(itp)?;
+        self.run_optimization_passes();
 
         Ok(())
     }
@@ -249,7 +337,10 @@ impl<'t> CodeGen<'t> {
 
     fn type_of(&self, param: &ItpTypeValue) -> AnyTypeEnum<'_> {
         match param {
-            ItpTypeValue::Int => self.context.i64_type().as_any_type_enum(),
+            ItpTypeValue::Int(width) => self
+                .context
+                .custom_width_int_type(width.bits)
+                .as_any_type_enum(),
             ItpTypeValue::Float => self.context.f64_type().as_any_type_enum(),
             ItpTypeValue::String => self
                 .context
@@ -258,9 +349,65 @@ impl<'t> CodeGen<'t> {
                 .as_any_type_enum(),
             ItpTypeValue::Char => self.context.i8_type().as_any_type_enum(),
             ItpTypeValue::Bool => self.context.bool_type().as_any_type_enum(),
-            ItpTypeValue::Array(_) => todo!(),
+            ItpTypeValue::Array { element, .. } => {
+                // Even with a known `length`, a standalone `ItpTypeValue`
+                // still lowers to just a pointer to the element type; the
+                // concrete `[N x T]` allocation happens where an `Array`
+                // literal is compiled.
+                let element = try_as_basic_type_enum(self.type_of(element))
+                    .expect("array element type must be a basic type");
+                element
+                    .ptr_type(AddressSpace::default())
+                    .as_any_type_enum()
+            }
+            ItpTypeValue::Tuple(elements) => {
+                // Like `Struct`, lowered to an actual `{ ... }` struct
+                // (rather than a pointer to one) so a constant index can
+                // `build_struct_gep` straight into it - unlike `Array`,
+                // there's no single shared element type to hand out a
+                // pointer to.
+                let element_types: Vec<BasicTypeEnum> = elements
+                    .iter()
+                    .map(|ty| {
+                        try_as_basic_type_enum(self.type_of(ty))
+                            .expect("tuple element type must be a basic type")
+                    })
+                    .collect();
+                self.context
+                    .struct_type(&element_types, false)
+                    .as_any_type_enum()
+            }
+            ItpTypeValue::Option(inner) => {
+                let payload = try_as_basic_type_enum(self.type_of(inner))
+                    .expect("option payload must be a basic type");
+                self.context
+                    .struct_type(&[self.context.bool_type().into(), payload], false)
+                    .as_any_type_enum()
+            }
+            ItpTypeValue::Struct { fields, .. } => {
+                // Lowered to an actual `{ ... }` struct rather than a
+                // pointer to one (unlike `Array`), so a struct embeds
+                // directly when it's an array element or another struct's
+                // field.
+                let field_types: Vec<BasicTypeEnum> = fields
+                    .iter()
+                    .map(|(_, ty)| {
+                        try_as_basic_type_enum(self.type_of(ty))
+                            .expect("struct field type must be a basic type")
+                    })
+                    .collect();
+                self.context
+                    .struct_type(&field_types, false)
+                    .as_any_type_enum()
+            }
             ItpTypeValue::Function { .. } => todo!(),
             ItpTypeValue::Void => self.context.void_type().as_any_type_enum(),
+            // No value of type `Never` is ever materialized - a call that
+            // produces one always ends its block in `unreachable` instead
+            // (see the `Call` arm of `ast()`) - but `type_of` still needs an
+            // answer for e.g. a `Conditional` whose merge block is itself
+            // unreachable.
+            ItpTypeValue::Never => self.context.void_type().as_any_type_enum(),
         }
     }
 
@@ -281,17 +428,451 @@ impl<'t> CodeGen<'t> {
             ItpConstantValue::Bool(b) => {
                 Ok(self.context.bool_type().const_int(*b as u64, false).into())
             }
-            ItpConstantValue::Array(_) => todo!(),
+            ItpConstantValue::Array(values) => {
+                let element_ty = match c.get_type() {
+                    ItpTypeValue::Array { element, .. } => {
+                        try_as_basic_type_enum(self.type_of(&element))?
+                    }
+                    _ => unreachable!("Array constant always lowers to an Array type"),
+                };
+                let array_ty = element_ty.array_type(values.len() as u32);
+                let alloca = self
+                    .builder
+                    .build_alloca(array_ty, "constarray")
+                    .map_err(|err| anyhow!(err))?;
+
+                for (i, value) in values.iter().enumerate() {
+                    let ItpValue::Constant(value) = value else {
+                        return Err(anyhow!("Array constant element must itself be constant"));
+                    };
+                    let element = self.get_constant(value)?;
+                    let element_ptr = unsafe {
+                        self.builder.build_gep(
+                            alloca,
+                            &[
+                                self.context.i64_type().const_zero(),
+                                self.context.i64_type().const_int(i as u64, false),
+                            ],
+                            "elementptr",
+                        )
+                    }
+                    .map_err(|err| anyhow!(err))?;
+                    self.builder
+                        .build_store(element_ptr, element)
+                        .map_err(|err| anyhow!(err))?;
+                }
+
+                Ok(alloca.as_basic_value_enum())
+            }
+            ItpConstantValue::Tuple(values) => {
+                let struct_ty = match self.type_of(&c.get_type()) {
+                    AnyTypeEnum::StructType(t) => t,
+                    _ => unreachable!("Tuple constant always lowers to a struct type"),
+                };
+                let values = values
+                    .iter()
+                    .map(|value| {
+                        let ItpValue::Constant(value) = value else {
+                            return Err(anyhow!("Tuple constant element must itself be constant"));
+                        };
+                        self.get_constant(value)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(struct_ty.const_named_struct(&values).into())
+            }
+            ItpConstantValue::Struct(_, fields) => {
+                let struct_ty = match self.type_of(&c.get_type()) {
+                    AnyTypeEnum::StructType(t) => t,
+                    _ => unreachable!("Struct constant always lowers to a struct type"),
+                };
+                let values = fields
+                    .iter()
+                    .map(|(_, value)| self.get_constant(value))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(struct_ty.const_named_struct(&values).into())
+            }
+            ItpConstantValue::Option(inner_ty, value) => {
+                let struct_ty = match self.type_of(&ItpTypeValue::Option(Box::new(inner_ty.clone())))
+                {
+                    AnyTypeEnum::StructType(t) => t,
+                    _ => unreachable!("Option always lowers to a struct type"),
+                };
+                let present = self.context.bool_type().const_int(value.is_some() as u64, false);
+                let payload = match value {
+                    Some(inner) => self.get_constant(inner)?,
+                    None => try_as_basic_type_enum(self.type_of(inner_ty))?.const_zero(),
+                };
+                Ok(struct_ty.const_named_struct(&[present.into(), payload]).into())
+            }
+        }
+    }
+
+    /// Declares `printf` in the module if it isn't already, for use by
+    /// `emit_panic` and any other diagnostic output.
+    fn get_or_declare_printf(&self) -> Result<FunctionValue<'t>> {
+        if let Some(printf) = self.module.get_function("printf") {
+            return Ok(printf);
         }
+
+        let i8_ptr_type = self.context.i8_type().ptr_type(AddressSpace::default());
+        let printf_type = self.context.i32_type().fn_type(&[i8_ptr_type.into()], true);
+        Ok(self.module.add_function("printf", printf_type, None))
+    }
+
+    /// Prints `message` and traps, for runtime invariant violations like
+    /// unwrapping a `none`. Never returns, so the caller should treat the
+    /// basic block it's emitted into as terminated.
+    fn emit_panic(&self, message: &str) -> Result<()> {
+        let printf_fn = self.get_or_declare_printf()?;
+        let formatted = format!("{}\n\0", message);
+        let fmt = self
+            .builder
+            .build_global_string_ptr(&formatted, "panic_msg")
+            .map_err(|err| anyhow!(err))?;
+
+        self.builder
+            .build_call(printf_fn, &[fmt.as_pointer_value().into()], "panic_print")
+            .map_err(|err| anyhow!(err))?;
+
+        let trap_fn = self.module.get_function("llvm.trap").unwrap_or_else(|| {
+            self.module
+                .add_function("llvm.trap", self.context.void_type().fn_type(&[], false), None)
+        });
+        self.builder
+            .build_call(trap_fn, &[], "trap")
+            .map_err(|err| anyhow!(err))?;
+        self.builder.build_unreachable().map_err(|err| anyhow!(err))?;
+
+        Ok(())
     }
 
     fn ast(&self, ast: &ItpAst, func: &FunctionValue<'t>) -> Result<AnyValueEnum<'t>> {
         match &ast.kind {
-            ItpAstKind::Constant(c) => Ok(self.get_constant(&c)?.as_any_value_enum()),
+            ItpAstKind::Constant { value, .. } => Ok(self.get_constant(value)?.as_any_value_enum()),
+            ItpAstKind::Array(elements) => {
+                let element_ty = match ast.get_type() {
+                    ItpTypeValue::Array { element, .. } => {
+                        try_as_basic_type_enum(self.type_of(&element))?
+                    }
+                    other => unreachable!("Array node must have an Array type, got {:?}", other),
+                };
+                let array_ty = element_ty.array_type(elements.len() as u32);
+                let alloca = self
+                    .builder
+                    .build_alloca(array_ty, "array")
+                    .map_err(|err| anyhow!(err))?;
+
+                for (i, element) in elements.iter().enumerate() {
+                    let value = self.ast(element, func)?;
+                    let element_ptr = unsafe {
+                        self.builder.build_gep(
+                            alloca,
+                            &[
+                                self.context.i64_type().const_zero(),
+                                self.context.i64_type().const_int(i as u64, false),
+                            ],
+                            "elementptr",
+                        )
+                    }
+                    .map_err(|err| anyhow!(err))?;
+                    self.builder
+                        .build_store(element_ptr, try_as_basic_value_enum(value)?)
+                        .map_err(|err| anyhow!(err))?;
+                }
+
+                Ok(alloca.as_any_value_enum())
+            }
+            ItpAstKind::Tuple(elements) => {
+                let struct_ty = match self.type_of(&ast.get_type()) {
+                    AnyTypeEnum::StructType(t) => t,
+                    other => unreachable!("Tuple node must lower to a struct type, got {:?}", other),
+                };
+                let alloca = self
+                    .builder
+                    .build_alloca(struct_ty, "tuple")
+                    .map_err(|err| anyhow!(err))?;
+
+                for (i, element) in elements.iter().enumerate() {
+                    let value = self.ast(element, func)?;
+                    let element_ptr = self
+                        .builder
+                        .build_struct_gep(alloca, i as u32, "elementptr")
+                        .map_err(|err| anyhow!(err))?;
+                    self.builder
+                        .build_store(element_ptr, try_as_basic_value_enum(value)?)
+                        .map_err(|err| anyhow!(err))?;
+                }
+
+                Ok(alloca.as_any_value_enum())
+            }
+            // A `Tuple`'s index is always a compile-time constant (checked
+            // in `Interpreter::build_index`), so it lowers to a direct
+            // `build_struct_gep` with no runtime bounds check, unlike an
+            // `Array`'s index below.
+            ItpAstKind::Index { value, index, .. } if matches!(value.get_type(), ItpTypeValue::Tuple(_)) => {
+                let tuple_val = self.ast(value, func)?;
+                let AnyValueEnum::PointerValue(tuple_ptr) = tuple_val else {
+                    return Err(anyhow!("Expected a tuple value to index"));
+                };
+
+                let ItpAstKind::Constant {
+                    value: ItpConstantValue::Int(i),
+                    ..
+                } = &index.kind
+                else {
+                    return Err(anyhow!("Tuple index must be a constant integer"));
+                };
+
+                let element_ptr = self
+                    .builder
+                    .build_struct_gep(tuple_ptr, *i as u32, "elementptr")
+                    .map_err(|err| anyhow!(err))?;
+                let loaded = self
+                    .builder
+                    .build_load(element_ptr, "element")
+                    .map_err(|err| anyhow!(err))?;
+
+                Ok(loaded.as_any_value_enum())
+            }
+            // A runtime-fallible `Array` access (see
+            // `Interpreter::build_index`) lowers to an `Option` rather than
+            // trapping: `present` is the in-bounds check, and the payload is
+            // the loaded element in `index_ok` or a zero of the element type
+            // in `index_oob` (same struct shape as `some`/`none` in
+            // `intrinsic_fns.rs`). The bound comes from the pointee's LLVM
+            // array type, so an unsized array (which `type_of` lowers to a
+            // bare element pointer) is rejected here with an error.
+            ItpAstKind::Index { value, index, result } if matches!(result, ItpTypeValue::Option(_)) => {
+                let array_val = self.ast(value, func)?;
+                let AnyValueEnum::PointerValue(array_ptr) = array_val else {
+                    return Err(anyhow!("Expected an array value to index"));
+                };
+
+                let array_ty = match array_ptr.get_type().get_element_type() {
+                    AnyTypeEnum::ArrayType(t) => t,
+                    _ => return Err(anyhow!("Expected an indexable array value")),
+                };
+                let len = array_ty.len();
+
+                let index_val = self.ast(index, func)?;
+                let index_int = match index_val {
+                    AnyValueEnum::IntValue(i) => i,
+                    AnyValueEnum::FloatValue(f) => self
+                        .builder
+                        .build_float_to_unsigned_int(f, self.context.i64_type(), "index")
+                        .map_err(|err| anyhow!(err))?,
+                    _ => return Err(anyhow!("Array index must be a number")),
+                };
+
+                let in_bounds = self
+                    .builder
+                    .build_int_compare(
+                        inkwell::IntPredicate::ULT,
+                        index_int,
+                        index_int.get_type().const_int(len as u64, false),
+                        "inbounds",
+                    )
+                    .map_err(|err| anyhow!(err))?;
+
+                let function = self
+                    .builder
+                    .get_insert_block()
+                    .and_then(|b| b.get_parent())
+                    .ok_or_else(|| anyhow!("Index used outside a function"))?;
+                let ok_block = self.context.append_basic_block(function, "index_ok");
+                let oob_block = self.context.append_basic_block(function, "index_oob");
+                let merge_block = self.context.append_basic_block(function, "index_merge");
+
+                let struct_ty = match self.type_of(result) {
+                    AnyTypeEnum::StructType(t) => t,
+                    other => {
+                        unreachable!("Option Index result must lower to a struct type, got {:?}", other)
+                    }
+                };
+                let alloca = self
+                    .builder
+                    .build_alloca(struct_ty, "opt")
+                    .map_err(|err| anyhow!(err))?;
+
+                self.builder
+                    .build_conditional_branch(in_bounds, ok_block, oob_block)
+                    .map_err(|err| anyhow!(err))?;
+
+                self.builder.position_at_end(ok_block);
+                let element_ptr = unsafe {
+                    self.builder.build_gep(
+                        array_ptr,
+                        &[self.context.i64_type().const_zero(), index_int],
+                        "elementptr",
+                    )
+                }
+                .map_err(|err| anyhow!(err))?;
+                let loaded = self
+                    .builder
+                    .build_load(element_ptr, "element")
+                    .map_err(|err| anyhow!(err))?;
+                let present_ptr = self
+                    .builder
+                    .build_struct_gep(alloca, 0, "present")
+                    .map_err(|err| anyhow!(err))?;
+                self.builder
+                    .build_store(present_ptr, self.context.bool_type().const_int(1, false))
+                    .map_err(|err| anyhow!(err))?;
+                let payload_ptr = self
+                    .builder
+                    .build_struct_gep(alloca, 1, "payload")
+                    .map_err(|err| anyhow!(err))?;
+                self.builder
+                    .build_store(payload_ptr, loaded)
+                    .map_err(|err| anyhow!(err))?;
+                self.builder
+                    .build_unconditional_branch(merge_block)
+                    .map_err(|err| anyhow!(err))?;
+
+                self.builder.position_at_end(oob_block);
+                let element_ty = match value.get_type() {
+                    ItpTypeValue::Array { element, .. } => try_as_basic_type_enum(self.type_of(&element))?,
+                    other => unreachable!("Array index's value must have an Array type, got {:?}", other),
+                };
+                let present_ptr = self
+                    .builder
+                    .build_struct_gep(alloca, 0, "present")
+                    .map_err(|err| anyhow!(err))?;
+                self.builder
+                    .build_store(present_ptr, self.context.bool_type().const_int(0, false))
+                    .map_err(|err| anyhow!(err))?;
+                let payload_ptr = self
+                    .builder
+                    .build_struct_gep(alloca, 1, "payload")
+                    .map_err(|err| anyhow!(err))?;
+                self.builder
+                    .build_store(payload_ptr, element_ty.const_zero())
+                    .map_err(|err| anyhow!(err))?;
+                self.builder
+                    .build_unconditional_branch(merge_block)
+                    .map_err(|err| anyhow!(err))?;
+
+                self.builder.position_at_end(merge_block);
+                let loaded_opt = self
+                    .builder
+                    .build_load(alloca, "opt_val")
+                    .map_err(|err| anyhow!(err))?;
+
+                Ok(loaded_opt.as_any_value_enum())
+            }
+            // A compile-time-proven-safe `Array` access (a constant index
+            // into a known-length array - see `Interpreter::build_index`):
+            // its bounds have already been checked there, so this is just a
+            // GEP and a load with no runtime check to redo.
+            ItpAstKind::Index { value, index, .. } => {
+                let array_val = self.ast(value, func)?;
+                let AnyValueEnum::PointerValue(array_ptr) = array_val else {
+                    return Err(anyhow!("Expected an array value to index"));
+                };
+
+                let index_val = self.ast(index, func)?;
+                let index_int = match index_val {
+                    AnyValueEnum::IntValue(i) => i,
+                    AnyValueEnum::FloatValue(f) => self
+                        .builder
+                        .build_float_to_unsigned_int(f, self.context.i64_type(), "index")
+                        .map_err(|err| anyhow!(err))?,
+                    _ => return Err(anyhow!("Array index must be a number")),
+                };
+
+                let element_ptr = unsafe {
+                    self.builder.build_gep(
+                        array_ptr,
+                        &[self.context.i64_type().const_zero(), index_int],
+                        "elementptr",
+                    )
+                }
+                .map_err(|err| anyhow!(err))?;
+                let loaded = self
+                    .builder
+                    .build_load(element_ptr, "element")
+                    .map_err(|err| anyhow!(err))?;
+
+                Ok(loaded.as_any_value_enum())
+            }
+            ItpAstKind::Struct { fields, .. } => {
+                let struct_ty = match self.type_of(&ast.get_type()) {
+                    AnyTypeEnum::StructType(t) => t,
+                    other => unreachable!("Struct node must lower to a struct type, got {:?}", other),
+                };
+                let alloca = self
+                    .builder
+                    .build_alloca(struct_ty, "struct")
+                    .map_err(|err| anyhow!(err))?;
+
+                for (i, (_, value)) in fields.iter().enumerate() {
+                    let value = self.ast(value, func)?;
+                    let field_ptr = self
+                        .builder
+                        .build_struct_gep(alloca, i as u32, "fieldptr")
+                        .map_err(|err| anyhow!(err))?;
+                    self.builder
+                        .build_store(field_ptr, try_as_basic_value_enum(value)?)
+                        .map_err(|err| anyhow!(err))?;
+                }
+
+                Ok(alloca.as_any_value_enum())
+            }
+            ItpAstKind::Field { value, field, .. } => {
+                let struct_val = self.ast(value, func)?;
+                let AnyValueEnum::PointerValue(struct_ptr) = struct_val else {
+                    return Err(anyhow!("Expected a struct value to access a field on"));
+                };
+
+                let index = struct_field_index(&value.get_type(), field)?;
+
+                let field_ptr = self
+                    .builder
+                    .build_struct_gep(struct_ptr, index, "fieldptr")
+                    .map_err(|err| anyhow!(err))?;
+                let loaded = self
+                    .builder
+                    .build_load(field_ptr, "fieldval")
+                    .map_err(|err| anyhow!(err))?;
+
+                Ok(loaded.as_any_value_enum())
+            }
+            ItpAstKind::SetField {
+                value,
+                field,
+                new_value,
+            } => {
+                let struct_val = self.ast(value, func)?;
+                let AnyValueEnum::PointerValue(struct_ptr) = struct_val else {
+                    return Err(anyhow!("Expected a struct value to set a field on"));
+                };
+
+                let index = struct_field_index(&value.get_type(), field)?;
+
+                let new_value = self.ast(new_value, func)?;
+                let field_ptr = self
+                    .builder
+                    .build_struct_gep(struct_ptr, index, "fieldptr")
+                    .map_err(|err| anyhow!(err))?;
+                self.builder
+                    .build_store(field_ptr, try_as_basic_value_enum(new_value)?)
+                    .map_err(|err| anyhow!(err))?;
+
+                Ok(new_value)
+            }
             ItpAstKind::Variable { name, .. } => {
                 let vars = self.variables.borrow();
                 let value = vars.get(&name.name).ok_or_else(|| {
-                    anyhow!("Variable {} not found in function {}", name.name, func.get_name().to_str().unwrap())
+                    intrinsic_fns::intrinsic_error(
+                        self,
+                        ast.line,
+                        ast.column,
+                        format!(
+                            "Variable '{}' not found in function {}",
+                            name.name,
+                            func.get_name().to_str().unwrap()
+                        ),
+                    )
                 })?;
                 let value = self.builder.build_load(*value, "load")
                     .map_err(|err| anyhow!(err))?;
@@ -326,28 +907,168 @@ impl<'t> CodeGen<'t> {
             ItpAstKind::Call {
                 function,
                 arguments,
-                ..
+                result,
             } => {
                 let mut args = vec![];
+                let arg_types: Vec<ItpTypeValue> = arguments.iter().map(|a| a.get_type()).collect();
 
                 for arg in arguments {
                     args.push(self.ast(arg, func)?);
                 }
 
-                Ok(self.call(function.name.clone(), &args)?)
+                let value = self.call(
+                    function.name.clone(),
+                    &args,
+                    &arg_types,
+                    result,
+                    ast.line,
+                    ast.column,
+                )?;
+
+                // A call to a `noreturn` function (`result` is the bottom
+                // type `Never`) never falls off the end of this block on
+                // its own - `build_call` alone doesn't terminate it, so we
+                // have to follow it with an explicit `unreachable`.
+                if *result == ItpTypeValue::Never {
+                    self.builder.build_unreachable().map_err(|err| anyhow!(err))?;
+                }
+
+                Ok(value)
+            }
+            ItpAstKind::Conditional {
+                condition,
+                then,
+                else_,
+            } => {
+                let cond_val = self.ast(condition, func)?;
+                let cond = match cond_val {
+                    AnyValueEnum::IntValue(i) => i,
+                    other => return Err(anyhow!("Condition must be a boolean value, got {:?}", other)),
+                };
+
+                let then_block = self.context.append_basic_block(*func, "then");
+                let else_block = self.context.append_basic_block(*func, "else");
+
+                self.builder
+                    .build_conditional_branch(cond, then_block, else_block)
+                    .map_err(|err| anyhow!(err))?;
+
+                // Only a branch that falls through (per `diverges`) gets the
+                // merge-block `br` appended; one that already ended in its
+                // own terminator (e.g. the `unreachable` after a `noreturn`
+                // call) must be left alone, or LLVM rejects the module for
+                // having two terminators in one block.
+                self.builder.position_at_end(then_block);
+                let then_val = self.ast(then, func)?;
+                let mut incoming = vec![];
+                if !diverges(then) {
+                    let then_end = self.builder.get_insert_block().unwrap();
+                    incoming.push((then_val, then_end));
+                }
+
+                self.builder.position_at_end(else_block);
+                let else_val = self.ast(else_, func)?;
+                if !diverges(else_) {
+                    let else_end = self.builder.get_insert_block().unwrap();
+                    incoming.push((else_val, else_end));
+                }
+
+                if incoming.is_empty() {
+                    // Both arms diverge, so nothing after this `if` is ever
+                    // reached either; there's no live block left to keep
+                    // emitting into except one that's unreachable itself.
+                    // The returned value is never actually used - whatever
+                    // consumes it is dead code too - so an arbitrary small
+                    // int stands in rather than trying to match `ast`'s
+                    // (possibly `Never`, not a real LLVM type) nominal type.
+                    let unreachable_block = self.context.append_basic_block(*func, "ifunreachable");
+                    self.builder.position_at_end(unreachable_block);
+                    self.builder.build_unreachable().map_err(|err| anyhow!(err))?;
+                    return Ok(self.context.i8_type().const_zero().as_any_value_enum());
+                }
+
+                let merge_block = self.context.append_basic_block(*func, "ifcont");
+                for (_, block) in &incoming {
+                    self.builder.position_at_end(*block);
+                    self.builder
+                        .build_unconditional_branch(merge_block)
+                        .map_err(|err| anyhow!(err))?;
+                }
+                self.builder.position_at_end(merge_block);
+
+                if incoming.len() == 1 {
+                    Ok(incoming[0].0)
+                } else {
+                    let phi = self
+                        .builder
+                        .build_phi(try_as_basic_type_enum(self.type_of(&ast.get_type()))?, "iftmp")
+                        .map_err(|err| anyhow!(err))?;
+                    for (value, block) in &incoming {
+                        phi.add_incoming(&[(&try_as_basic_value_enum(*value)?, *block)]);
+                    }
+                    Ok(phi.as_any_value_enum())
+                }
             }
+            ItpAstKind::Loop { condition, body } => {
+                let loop_cond_block = self.context.append_basic_block(*func, "loopcond");
+                let loop_body_block = self.context.append_basic_block(*func, "loopbody");
+                let after_loop_block = self.context.append_basic_block(*func, "afterloop");
+
+                self.builder
+                    .build_unconditional_branch(loop_cond_block)
+                    .map_err(|err| anyhow!(err))?;
+
+                self.builder.position_at_end(loop_cond_block);
+                let cond_val = self.ast(condition, func)?;
+                let cond = match cond_val {
+                    AnyValueEnum::IntValue(i) => i,
+                    other => return Err(anyhow!("Condition must be a boolean value, got {:?}", other)),
+                };
+                self.builder
+                    .build_conditional_branch(cond, loop_body_block, after_loop_block)
+                    .map_err(|err| anyhow!(err))?;
+
+                self.builder.position_at_end(loop_body_block);
+                self.ast(body, func)?;
+                // The body can itself diverge (e.g. end in a `noreturn`
+                // call), in which case it already terminated its block and
+                // looping back here would be a second terminator.
+                if !diverges(body) {
+                    self.builder
+                        .build_unconditional_branch(loop_cond_block)
+                        .map_err(|err| anyhow!(err))?;
+                }
+
+                self.builder.position_at_end(after_loop_block);
+                Ok(self.context.i8_type().const_zero().as_any_value_enum())
+            }
+            // Emits no instructions; like `Loop`'s, the returned value is
+            // only a placeholder.
+            ItpAstKind::Error => Ok(self.context.i8_type().const_zero().as_any_value_enum()),
         }
     }
 
-    fn call(&self, name: String, args: &[AnyValueEnum<'t>]) -> Result<AnyValueEnum<'t>> {
-        if let Some(v) = check_intrinsic_fn(&name, self, args)? {
+    fn call(
+        &self,
+        name: String,
+        args: &[AnyValueEnum<'t>],
+        arg_types: &[ItpTypeValue],
+        result_type: &ItpTypeValue,
+        line: usize,
+        column: usize,
+    ) -> Result<AnyValueEnum<'t>> {
+        if let Some(v) = check_intrinsic_fn(&name, self, args, arg_types, result_type, line, column)? {
             return Ok(v);
         }
 
-        let function = self
-            .module
-            .get_function(&name)
-            .ok_or_else(|| anyhow!("Function '{}' not found in the module", name))?;
+        let function = self.module.get_function(&name).ok_or_else(|| {
+            intrinsic_fns::intrinsic_error(
+                self,
+                line,
+                column,
+                format!("Function '{}' not found in the module", name),
+            )
+        })?;
 
         let args = args
             .iter()
@@ -363,9 +1084,9 @@ impl<'t> CodeGen<'t> {
     }
 }
 
-pub fn compile_to_llvm_ir(itp: &Interpreter) -> Result<String> {
+pub fn compile_to_llvm_ir(itp: &Interpreter, source: &str, options: CompileOptions) -> Result<String> {
     let context = Context::create();
-    let codegen = (&CodeGen::new(&context)) as *const CodeGen<'_>;
+    let codegen = (&CodeGen::new(&context, source, options)) as *const CodeGen<'_>;
 
     unsafe {
         (*codegen).compile(itp)?;
@@ -376,9 +1097,9 @@ pub fn compile_to_llvm_ir(itp: &Interpreter) -> Result<String> {
     Ok(ir)
 }
 
-pub fn run_jit(itp: &Interpreter) -> Result<()> {
+pub fn run_jit(itp: &Interpreter, source: &str, options: CompileOptions) -> Result<()> {
     let context = Context::create();
-    let codegen = (&CodeGen::new(&context)) as *const CodeGen<'_>;
+    let codegen = (&CodeGen::new(&context, source, options)) as *const CodeGen<'_>;
 
     unsafe {
         (*codegen).jit_compile(itp)?.call();
@@ -386,3 +1107,40 @@ pub fn run_jit(itp: &Interpreter) -> Result<()> {
 
     Ok(())
 }
+
+pub fn compile_to_file(itp: &Interpreter, source: &str, filename: &str, options: CompileOptions) -> Result<()> {
+    let context = Context::create();
+    let codegen = CodeGen::new(&context, source, options);
+
+    codegen.compile(itp)?;
+
+    if codegen
+        .module
+        .write_bitcode_to_path(std::path::Path::new(filename))
+    {
+        Ok(())
+    } else {
+        Err(anyhow!("Unable to write bitcode to file"))
+    }
+}
+
+/// Emits a native object file for `options.target_triple` (the host, if
+/// unset) via `TargetMachine::write_to_file`, rather than the LLVM-bitcode
+/// `compile_to_file` writes. Link the result with a system linker (or feed
+/// it to `cc`) to get a standalone executable.
+pub fn compile_to_object_file(
+    itp: &Interpreter,
+    source: &str,
+    filename: &str,
+    options: CompileOptions,
+) -> Result<()> {
+    let context = Context::create();
+    let codegen = CodeGen::new(&context, source, options);
+
+    codegen.compile(itp)?;
+
+    let target_machine = codegen.target_machine()?;
+    target_machine
+        .write_to_file(&codegen.module, FileType::Object, std::path::Path::new(filename))
+        .map_err(|err| anyhow!(err))
+}
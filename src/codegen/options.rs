@@ -0,0 +1,31 @@
+use inkwell::targets::{CodeModel, RelocMode};
+use inkwell::OptimizationLevel;
+
+/// Knobs for [`super::CodeGen`]'s backend, separate from anything the
+/// interpreter infers: how hard to optimize, and what machine to target.
+/// `compile_to_llvm_ir`/`run_jit`/`compile_to_file`/`compile_to_object_file`
+/// all take one of these, so a caller can ask for an optimized, cross-
+/// compiled artifact instead of the unoptimized-for-the-host JIT default.
+#[derive(Debug, Clone)]
+pub struct CompileOptions {
+    /// `OptimizationLevel::None` skips the pass pipeline entirely; anything
+    /// else runs the classic mem2reg/instcombine/reassociate/gvn/simplifycfg
+    /// sequence, with `Aggressive` additionally inlining.
+    pub opt_level: OptimizationLevel,
+    /// `None` targets the host (`TargetMachine::get_default_triple()`);
+    /// `Some(triple)` cross-compiles, e.g. `"x86_64-unknown-linux-gnu"`.
+    pub target_triple: Option<String>,
+    pub reloc_mode: RelocMode,
+    pub code_model: CodeModel,
+}
+
+impl Default for CompileOptions {
+    fn default() -> Self {
+        CompileOptions {
+            opt_level: OptimizationLevel::None,
+            target_triple: None,
+            reloc_mode: RelocMode::Default,
+            code_model: CodeModel::Default,
+        }
+    }
+}
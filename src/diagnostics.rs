@@ -0,0 +1,103 @@
+use std::fmt;
+
+/// How serious a [`Diagnostic`] is. Both are collected the same way by
+/// [`Interpreter::interpret`]; neither stops codegen on its own, that is
+/// left to the caller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// Where a [`Diagnostic`] points, in the same 1-based line/column terms as
+/// `ParsedAst`/`ItpAst`. `length` is how many columns to underline; nodes
+/// that don't track their own width just get a single-caret span.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    pub length: usize,
+}
+
+impl Span {
+    pub fn new(line: usize, column: usize) -> Self {
+        Span {
+            line,
+            column,
+            length: 1,
+        }
+    }
+}
+
+/// One problem found while interpreting a program, carrying enough location
+/// info to render a snippet back against the original source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub span: Span,
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>, span: Span) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            message: message.into(),
+            span,
+        }
+    }
+
+    pub fn warning(message: impl Into<String>, span: Span) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            message: message.into(),
+            span,
+        }
+    }
+
+    /// Renders this diagnostic against `source`, annotate-snippets style:
+    /// the offending line in context with a caret underline beneath the
+    /// span.
+    ///
+    /// ```text
+    /// error: Variable `foo` not found
+    ///   --> line 3, column 12
+    ///   |
+    /// 3 |     (print foo)
+    ///   |            ^^^
+    /// ```
+    pub fn render(&self, source: &str) -> String {
+        let line_text = source
+            .lines()
+            .nth(self.span.line.saturating_sub(1))
+            .unwrap_or("");
+        let line_no = self.span.line.to_string();
+        let gutter = " ".repeat(line_no.len());
+        let underline = format!(
+            "{}{}",
+            " ".repeat(self.span.column.saturating_sub(1)),
+            "^".repeat(self.span.length.max(1))
+        );
+
+        format!(
+            "{severity}: {message}\n{gutter} --> line {line}, column {column}\n{gutter} |\n{line_no} | {line_text}\n{gutter} | {underline}",
+            severity = self.severity,
+            message = self.message,
+            gutter = gutter,
+            line = self.span.line,
+            column = self.span.column,
+            line_no = line_no,
+            line_text = line_text,
+            underline = underline,
+        )
+    }
+}
@@ -13,13 +13,15 @@ pub struct ItpAst {
 }
 
 impl ItpAst {
+    /// Bare error message, with no line/column baked in: the caller
+    /// propagates this via `?` up to `Interpreter::interpret`, which wraps
+    /// `err.to_string()` in a `Diagnostic` (see `Span::new`), so the snippet
+    /// renderer is the only thing that ever prints a location. That
+    /// diagnostic is positioned at the `line`/`column` of the function-body
+    /// statement being finalized, not at this node's own when it is nested
+    /// inside that statement.
     pub fn error(&self, message: &str) -> Error {
-        anyhow!(
-            "Error at line {} column {}: {}",
-            self.line,
-            self.column,
-            message
-        )
+        anyhow!("{}", message)
     }
 
     pub fn err<T>(&self, message: &str) -> Result<T> {
@@ -28,26 +30,44 @@ impl ItpAst {
 
     pub fn get_type(&self) -> ItpTypeValue {
         match &self.kind {
-            ItpAstKind::Constant(value) => value.get_type(),
-            ItpAstKind::Array(values) => {
-                ItpTypeValue::Array(Box::new(if values.is_empty() {
+            ItpAstKind::Constant { result, .. } => result.clone(),
+            ItpAstKind::Array(values) => ItpTypeValue::Array {
+                element: Box::new(if values.is_empty() {
                     ItpTypeValue::Void
                 } else {
                     values[0].get_type()
-                }))
+                }),
+                length: Some(values.len()),
+            },
+            ItpAstKind::Tuple(values) => {
+                ItpTypeValue::Tuple(values.iter().map(|v| v.get_type()).collect())
             }
             ItpAstKind::Variable { result, .. } => result.clone(),
             ItpAstKind::SetVariable { .. } => ItpTypeValue::Void,
             ItpAstKind::Conditional { then, else_, .. } => {
-                if then.get_type() != else_.get_type() {
-                    ItpTypeValue::Void
-                } else {
-                    then.get_type()
+                // A diverging arm (see `codegen::diverges`) never actually
+                // produces a value of its nominal `Never` type, so it
+                // shouldn't stop the other, real arm's type from winning.
+                match (then.get_type(), else_.get_type()) {
+                    (ItpTypeValue::Never, other) | (other, ItpTypeValue::Never) => other,
+                    (then_ty, else_ty) if then_ty == else_ty => then_ty,
+                    _ => ItpTypeValue::Void,
                 }
             }
             ItpAstKind::Loop { .. } => ItpTypeValue::Void,
             ItpAstKind::Param { result, .. } => result.clone(),
             ItpAstKind::Call { result, .. } => result.clone(),
+            ItpAstKind::Index { result, .. } => result.clone(),
+            ItpAstKind::Struct { name, fields } => ItpTypeValue::Struct {
+                name: name.clone(),
+                fields: fields
+                    .iter()
+                    .map(|(field, value)| (field.clone(), value.get_type()))
+                    .collect(),
+            },
+            ItpAstKind::Field { result, .. } => result.clone(),
+            ItpAstKind::SetField { .. } => ItpTypeValue::Void,
+            ItpAstKind::Error => ItpTypeValue::Void,
         }
     }
 }
@@ -55,8 +75,21 @@ impl ItpAst {
 /// The different kinds of AST nodes
 #[derive(Debug, PartialEq, Clone)]
 pub enum ItpAstKind {
-    Constant(ItpConstantValue),
+    /// A literal or constant-propagated value. `result` starts out as
+    /// `value.get_type()` for everything except an integer literal, which
+    /// instead gets a fresh `NumericVar` so it can resolve to `Int` or
+    /// `Float` depending on how it's used; `infer::apply_subst_ast` is what
+    /// finalizes `result` (and rewrites `value` to match if it widened to
+    /// `Float`).
+    Constant {
+        value: ItpConstantValue,
+        result: ItpTypeValue,
+    },
     Array(Vec<ItpAst>),
+    /// A `{a, b, c}` literal - unlike `Array`, elements may have different
+    /// types (see `ItpTypeValue::Tuple`), so only a constant index into one
+    /// type-checks (see `Interpreter::build_index`).
+    Tuple(Vec<ItpAst>),
     Variable {
         name: Identifier,
         result: ItpTypeValue,
@@ -84,4 +117,43 @@ pub enum ItpAstKind {
         arguments: Vec<ItpAst>,
         result: ItpTypeValue,
     },
+    /// Indexes into an array/tuple-like `value` (see
+    /// `Interpreter::build_index`). Whenever the access is provably
+    /// in-bounds at compile time - a constant index into an `Array` literal
+    /// or a known-length `Array`, or any index into a `Tuple` (which only
+    /// ever accepts a constant one) - `result` is that element's own precise
+    /// type. Anything else is a genuinely runtime-fallible `Array` access,
+    /// so `result` is `Option` of the shared element type instead of
+    /// trapping on an out-of-bounds index.
+    Index {
+        value: Box<ItpAst>,
+        index: Box<ItpAst>,
+        result: ItpTypeValue,
+    },
+    /// A `@struct`-typed literal; `name` must match a layout registered by
+    /// `@struct` and `fields` is in that layout's declared order.
+    Struct {
+        name: String,
+        fields: Vec<(String, ItpAst)>,
+    },
+    /// Reads `field` off of a struct `value`, with `field`'s index within
+    /// the struct resolved positionally by `Interpreter::build_field`/the
+    /// codegen `Field` arm, since LLVM struct GEPs are by index, not name.
+    Field {
+        value: Box<ItpAst>,
+        field: String,
+        result: ItpTypeValue,
+    },
+    /// Writes `new_value` into `field` of a struct `value`. Like
+    /// `SetVariable`, always has type `Void`.
+    SetField {
+        value: Box<ItpAst>,
+        field: String,
+        new_value: Box<ItpAst>,
+    },
+    /// Placeholder left in place of a node that failed to interpret, so a
+    /// recoverable problem (e.g. an unknown variable) can be recorded as a
+    /// diagnostic and interpretation can keep going instead of aborting.
+    /// Always has type `Void` and lowers to nothing in codegen.
+    Error,
 }
@@ -0,0 +1,461 @@
+//! Hindley-Milner-style type inference (Algorithm W), so `@fn` parameters
+//! and return types never need an annotation syntax: `macros::fn_macro`
+//! gives every unannotated parameter/return a [`fresh_var`], ordinary
+//! interpretation unifies those vars against how the parameter is actually
+//! used (a `Call`'s arguments against its callee's parameters, a
+//! `set-field`'s value against the field's declared type, and so on - see
+//! each macro/`Interpreter` method that calls [`unify`]), and once a
+//! function's whole body has been walked, [`generalize`] turns any
+//! variable still unbound into a named generic so
+//! `ItpFunctionParameters::instantiate` can hand each call site its own
+//! fresh copy (let-polymorphism). [`apply_subst_ast`] is the final pass
+//! that bakes every accumulated binding back into the AST, so codegen
+//! only ever sees concrete, var-free types.
+
+use std::cell::Cell;
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use super::ast::{ItpAst, ItpAstKind};
+use super::value::{IntWidth, ItpConstantValue, ItpFunctionParameters, ItpTypeValue};
+
+thread_local! {
+    static NEXT_VAR: Cell<u32> = Cell::new(0);
+}
+
+/// Allocates a fresh, globally-unique type variable.
+pub fn fresh_var() -> ItpTypeValue {
+    NEXT_VAR.with(|next| {
+        let id = next.get();
+        next.set(id + 1);
+        ItpTypeValue::Var(id)
+    })
+}
+
+/// Allocates a fresh, globally-unique `NumericVar`, for an integer literal's
+/// own type (see [`ItpTypeValue::NumericVar`]). Shares `NEXT_VAR`'s counter
+/// with `fresh_var`, so ids stay unique regardless of which kind of variable
+/// created them.
+pub fn fresh_numeric_var() -> ItpTypeValue {
+    NEXT_VAR.with(|next| {
+        let id = next.get();
+        next.set(id + 1);
+        ItpTypeValue::NumericVar(id)
+    })
+}
+
+/// Maps a type-variable id to the type it has been bound to.
+pub type Substitution = HashMap<u32, ItpTypeValue>;
+
+/// Follows `Var` chains through `subst` to their representative type.
+pub fn resolve(subst: &Substitution, ty: &ItpTypeValue) -> ItpTypeValue {
+    match ty {
+        ItpTypeValue::Var(id) | ItpTypeValue::NumericVar(id) => match subst.get(id) {
+            Some(bound) => resolve(subst, bound),
+            None => ty.clone(),
+        },
+        ItpTypeValue::Array { element, length } => ItpTypeValue::Array {
+            element: Box::new(resolve(subst, element)),
+            length: *length,
+        },
+        ItpTypeValue::Tuple(elements) => {
+            ItpTypeValue::Tuple(elements.iter().map(|t| resolve(subst, t)).collect())
+        }
+        ItpTypeValue::Option(inner) => ItpTypeValue::Option(Box::new(resolve(subst, inner))),
+        ItpTypeValue::Struct { name, fields } => ItpTypeValue::Struct {
+            name: name.clone(),
+            fields: fields
+                .iter()
+                .map(|(field, t)| (field.clone(), resolve(subst, t)))
+                .collect(),
+        },
+        ItpTypeValue::Function {
+            parameters,
+            return_type,
+        } => ItpTypeValue::Function {
+            parameters: resolve_parameters(subst, parameters),
+            return_type: Box::new(resolve(subst, return_type)),
+        },
+        _ => ty.clone(),
+    }
+}
+
+/// Resolves every parameter type through `subst`, leaving `generics`/
+/// `variadic` untouched - same shape as [`resolve`], just threaded through
+/// the extra layer `ItpFunctionParameters` wraps its types in.
+fn resolve_parameters(
+    subst: &Substitution,
+    parameters: &ItpFunctionParameters,
+) -> ItpFunctionParameters {
+    ItpFunctionParameters {
+        generics: parameters.generics.clone(),
+        parameters: parameters
+            .parameters
+            .iter()
+            .map(|(name, t)| (name.clone(), resolve(subst, t)))
+            .collect(),
+        variadic: parameters.variadic,
+    }
+}
+
+fn occurs(subst: &Substitution, id: u32, ty: &ItpTypeValue) -> bool {
+    match resolve(subst, ty) {
+        ItpTypeValue::Var(other) | ItpTypeValue::NumericVar(other) => other == id,
+        ItpTypeValue::Array { element, .. } => occurs(subst, id, &element),
+        ItpTypeValue::Tuple(elements) => elements.iter().any(|t| occurs(subst, id, t)),
+        ItpTypeValue::Option(inner) => occurs(subst, id, &inner),
+        ItpTypeValue::Struct { fields, .. } => {
+            fields.iter().any(|(_, t)| occurs(subst, id, t))
+        }
+        ItpTypeValue::Function {
+            parameters,
+            return_type,
+        } => {
+            parameters
+                .parameters
+                .iter()
+                .any(|(_, t)| occurs(subst, id, t))
+                || occurs(subst, id, &return_type)
+        }
+        _ => false,
+    }
+}
+
+/// Unifies `a` and `b`, recording any new variable bindings in `subst`.
+///
+/// Both sides are resolved through the current substitution first. A
+/// remaining `Var` is bound to the other side (after an occurs-check that
+/// rejects infinite types); `Array`/`Function` types unify componentwise.
+/// Returns the two resolved, mismatched types on failure.
+pub fn unify(
+    subst: &mut Substitution,
+    a: &ItpTypeValue,
+    b: &ItpTypeValue,
+) -> Result<(), (ItpTypeValue, ItpTypeValue)> {
+    let a = resolve(subst, a);
+    let b = resolve(subst, b);
+
+    match (&a, &b) {
+        (ItpTypeValue::Var(x), ItpTypeValue::Var(y)) if x == y => Ok(()),
+        (ItpTypeValue::Var(x), _) => {
+            if occurs(subst, *x, &b) {
+                Err((a, b))
+            } else {
+                subst.insert(*x, b);
+                Ok(())
+            }
+        }
+        (_, ItpTypeValue::Var(y)) => {
+            if occurs(subst, *y, &a) {
+                Err((a, b))
+            } else {
+                subst.insert(*y, a);
+                Ok(())
+            }
+        }
+        // `Never` is the bottom type: a diverging branch never actually
+        // produces a value, so it should never block the other arm of a
+        // `Conditional` from unifying to whatever concrete type it has.
+        (ItpTypeValue::Never, _) | (_, ItpTypeValue::Never) => Ok(()),
+        (ItpTypeValue::NumericVar(x), ItpTypeValue::NumericVar(y)) if x == y => Ok(()),
+        // A `NumericVar` is a `Var` constrained to only ever stand for a
+        // number, so unlike the general `Var` arms above it rejects
+        // anything that isn't `Int`/`Float`/another `NumericVar` - binding
+        // it to e.g. `String` would let a numeric literal masquerade as one.
+        (ItpTypeValue::NumericVar(x), ItpTypeValue::Int(_))
+        | (ItpTypeValue::NumericVar(x), ItpTypeValue::Float)
+        | (ItpTypeValue::NumericVar(x), ItpTypeValue::NumericVar(_)) => {
+            subst.insert(*x, b);
+            Ok(())
+        }
+        (ItpTypeValue::Int(_), ItpTypeValue::NumericVar(y))
+        | (ItpTypeValue::Float, ItpTypeValue::NumericVar(y)) => {
+            subst.insert(*y, a);
+            Ok(())
+        }
+        (ItpTypeValue::NumericVar(_), _) | (_, ItpTypeValue::NumericVar(_)) => Err((a, b)),
+        (
+            ItpTypeValue::Array {
+                element: ea,
+                length: la,
+            },
+            ItpTypeValue::Array {
+                element: eb,
+                length: lb,
+            },
+        ) => {
+            // A sized array unifies with an unsized one (the unsized side
+            // just hasn't been pinned down yet); two sized arrays only
+            // unify if their lengths actually match.
+            match (la, lb) {
+                (Some(la), Some(lb)) if la != lb => return Err((a, b)),
+                _ => {}
+            }
+            unify(subst, ea, eb)
+        }
+        (ItpTypeValue::Tuple(ta), ItpTypeValue::Tuple(tb)) if ta.len() == tb.len() => {
+            for (ea, eb) in ta.iter().zip(tb.iter()) {
+                unify(subst, ea, eb)?;
+            }
+            Ok(())
+        }
+        (ItpTypeValue::Option(ia), ItpTypeValue::Option(ib)) => unify(subst, ia, ib),
+        (
+            ItpTypeValue::Struct {
+                name: na,
+                fields: fa,
+            },
+            ItpTypeValue::Struct {
+                name: nb,
+                fields: fb,
+            },
+        ) if na == nb && fa.len() == fb.len() => {
+            for ((_, ta), (_, tb)) in fa.iter().zip(fb.iter()) {
+                unify(subst, ta, tb)?;
+            }
+            Ok(())
+        }
+        (
+            ItpTypeValue::Function {
+                parameters: pa,
+                return_type: ra,
+            },
+            ItpTypeValue::Function {
+                parameters: pb,
+                return_type: rb,
+            },
+        ) => {
+            if pa.parameters.len() != pb.parameters.len() {
+                return Err((a, b));
+            }
+            for ((_, ta), (_, tb)) in pa.parameters.iter().zip(pb.parameters.iter()) {
+                unify(subst, ta, tb)?;
+            }
+            unify(subst, ra, rb)
+        }
+        _ if a == b => Ok(()),
+        _ => Err((a, b)),
+    }
+}
+
+/// Resolves every type-carrying field of `ast` through `subst`, recursing
+/// into children. Errors if a var is left unresolved, since codegen can't
+/// lower an unconstrained type variable; the error carries no location of
+/// its own (see `ItpAst::error`).
+pub fn apply_subst_ast(subst: &Substitution, ast: &mut ItpAst) -> Result<()> {
+    match &mut ast.kind {
+        ItpAstKind::Constant { value, result } => {
+            *result = finalize(subst, result, ast)?;
+            if let (ItpConstantValue::Int(i), ItpTypeValue::Float) = (&*value, &*result) {
+                *value = ItpConstantValue::Float(*i as f64);
+            }
+        }
+        ItpAstKind::Error => {}
+        ItpAstKind::Array(values) => {
+            for value in values {
+                apply_subst_ast(subst, value)?;
+            }
+        }
+        ItpAstKind::Tuple(values) => {
+            for value in values {
+                apply_subst_ast(subst, value)?;
+            }
+        }
+        ItpAstKind::Struct { fields, .. } => {
+            for (_, value) in fields {
+                apply_subst_ast(subst, value)?;
+            }
+        }
+        ItpAstKind::Field { value, result, .. } => {
+            apply_subst_ast(subst, value)?;
+            *result = finalize(subst, result, ast)?;
+        }
+        ItpAstKind::SetField {
+            value, new_value, ..
+        } => {
+            apply_subst_ast(subst, value)?;
+            apply_subst_ast(subst, new_value)?;
+        }
+        ItpAstKind::Variable { result, .. } => *result = finalize(subst, result, ast)?,
+        ItpAstKind::SetVariable { value, .. } => apply_subst_ast(subst, value)?,
+        ItpAstKind::Param { result, .. } => *result = finalize(subst, result, ast)?,
+        ItpAstKind::Conditional {
+            condition,
+            then,
+            else_,
+        } => {
+            apply_subst_ast(subst, condition)?;
+            apply_subst_ast(subst, then)?;
+            apply_subst_ast(subst, else_)?;
+        }
+        ItpAstKind::Loop { condition, body } => {
+            apply_subst_ast(subst, condition)?;
+            apply_subst_ast(subst, body)?;
+        }
+        ItpAstKind::Call {
+            arguments, result, ..
+        } => {
+            for argument in arguments.iter_mut() {
+                apply_subst_ast(subst, argument)?;
+            }
+            *result = finalize(subst, result, ast)?;
+        }
+        ItpAstKind::Index {
+            value,
+            index,
+            result,
+        } => {
+            apply_subst_ast(subst, value)?;
+            apply_subst_ast(subst, index)?;
+            *result = finalize(subst, result, ast)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn finalize(subst: &Substitution, ty: &ItpTypeValue, ast: &ItpAst) -> Result<ItpTypeValue> {
+    let resolved = default_numeric_vars(&resolve(subst, ty));
+    if contains_var(&resolved) {
+        return ast.err(&format!(
+            "Could not infer a concrete type, left with {:?}",
+            resolved
+        ));
+    }
+    Ok(resolved)
+}
+
+/// Replaces any `NumericVar` left unconstrained by `resolve` with
+/// `Int(IntWidth::I64)`, the default a numeric literal takes when nothing
+/// ever unified it against a concrete `Int`/`Float`. Must only run once,
+/// here at the end of inference - doing this inside `resolve` itself would
+/// default a literal before a later unification against `Float` got the
+/// chance to widen it.
+fn default_numeric_vars(ty: &ItpTypeValue) -> ItpTypeValue {
+    match ty {
+        ItpTypeValue::NumericVar(_) => ItpTypeValue::Int(IntWidth::I64),
+        ItpTypeValue::Array { element, length } => ItpTypeValue::Array {
+            element: Box::new(default_numeric_vars(element)),
+            length: *length,
+        },
+        ItpTypeValue::Tuple(elements) => {
+            ItpTypeValue::Tuple(elements.iter().map(default_numeric_vars).collect())
+        }
+        ItpTypeValue::Option(inner) => ItpTypeValue::Option(Box::new(default_numeric_vars(inner))),
+        ItpTypeValue::Struct { name, fields } => ItpTypeValue::Struct {
+            name: name.clone(),
+            fields: fields
+                .iter()
+                .map(|(field, t)| (field.clone(), default_numeric_vars(t)))
+                .collect(),
+        },
+        ItpTypeValue::Function {
+            parameters,
+            return_type,
+        } => ItpTypeValue::Function {
+            parameters: ItpFunctionParameters {
+                generics: parameters.generics.clone(),
+                parameters: parameters
+                    .parameters
+                    .iter()
+                    .map(|(name, t)| (name.clone(), default_numeric_vars(t)))
+                    .collect(),
+                variadic: parameters.variadic,
+            },
+            return_type: Box::new(default_numeric_vars(return_type)),
+        },
+        _ => ty.clone(),
+    }
+}
+
+/// Generalizes the free type variables reachable from `targets` into a
+/// let-polymorphic scheme: each distinct variable left unbound by `subst`
+/// gets its own fresh generic name (shared across every occurrence, so e.g.
+/// a parameter and the return type that got unified together during
+/// body-checking end up quantified over the *same* name), and `targets` is
+/// rewritten in place to use those names. Already-concrete types are left
+/// alone. Returns the generated generic names, suitable for
+/// `ItpFunctionParameters::generics` so `instantiate` can hand each call
+/// site fresh variables in their place.
+pub fn generalize(subst: &Substitution, targets: &mut [&mut ItpTypeValue]) -> Vec<String> {
+    let mut names: HashMap<u32, String> = HashMap::new();
+    let mut generics = vec![];
+
+    for target in targets.iter_mut() {
+        **target = generalize_one(subst, target, &mut names, &mut generics);
+    }
+
+    generics
+}
+
+fn generalize_one(
+    subst: &Substitution,
+    ty: &ItpTypeValue,
+    names: &mut HashMap<u32, String>,
+    generics: &mut Vec<String>,
+) -> ItpTypeValue {
+    match resolve(subst, ty) {
+        ItpTypeValue::Var(id) => {
+            let name = names.entry(id).or_insert_with(|| {
+                let name = format!("T{}", generics.len());
+                generics.push(name.clone());
+                name.clone()
+            });
+            ItpTypeValue::Generic(name.clone())
+        }
+        ItpTypeValue::Array { element, length } => ItpTypeValue::Array {
+            element: Box::new(generalize_one(subst, &element, names, generics)),
+            length,
+        },
+        ItpTypeValue::Tuple(elements) => ItpTypeValue::Tuple(
+            elements
+                .into_iter()
+                .map(|t| generalize_one(subst, &t, names, generics))
+                .collect(),
+        ),
+        ItpTypeValue::Option(inner) => ItpTypeValue::Option(Box::new(generalize_one(
+            subst, &inner, names, generics,
+        ))),
+        ItpTypeValue::Struct { name, fields } => ItpTypeValue::Struct {
+            name,
+            fields: fields
+                .into_iter()
+                .map(|(field, t)| (field, generalize_one(subst, &t, names, generics)))
+                .collect(),
+        },
+        ItpTypeValue::Function {
+            parameters,
+            return_type,
+        } => ItpTypeValue::Function {
+            parameters: ItpFunctionParameters {
+                generics: parameters.generics.clone(),
+                parameters: parameters
+                    .parameters
+                    .into_iter()
+                    .map(|(name, t)| (name, generalize_one(subst, &t, names, generics)))
+                    .collect(),
+                variadic: parameters.variadic,
+            },
+            return_type: Box::new(generalize_one(subst, &return_type, names, generics)),
+        },
+        other => other,
+    }
+}
+
+fn contains_var(ty: &ItpTypeValue) -> bool {
+    match ty {
+        ItpTypeValue::Var(_) | ItpTypeValue::NumericVar(_) => true,
+        ItpTypeValue::Array { element, .. } => contains_var(element),
+        ItpTypeValue::Tuple(elements) => elements.iter().any(contains_var),
+        ItpTypeValue::Option(inner) => contains_var(inner),
+        ItpTypeValue::Struct { fields, .. } => fields.iter().any(|(_, t)| contains_var(t)),
+        ItpTypeValue::Function {
+            parameters,
+            return_type,
+        } => {
+            parameters.parameters.iter().any(|(_, t)| contains_var(t)) || contains_var(return_type)
+        }
+        _ => false,
+    }
+}
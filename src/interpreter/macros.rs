@@ -4,6 +4,7 @@ use crate::parser::ast::ParsedAst;
 
 use super::{
     ast::{ItpAst, ItpAstKind},
+    infer,
     scope::Scope,
     value::{ItpFunctionParameters, ItpTypeValue, ItpValue, UnItpedFunctionValue},
     Interpreter,
@@ -25,6 +26,7 @@ pub fn macros() -> HashMap<String, Macro> {
     add_macro!(macros, "set", set_macro);
     add_macro!(macros, "if", if_macro);
     add_macro!(macros, "while", while_macro);
+    add_macro!(macros, "struct", struct_macro);
 
     macros
 }
@@ -39,12 +41,12 @@ fn fn_macro(
     let args = ast[1].as_array()?;
     let body = &ast[2..];
 
+    // Parameters have no type annotation syntax yet, so each gets a fresh
+    // type variable that the inference pass resolves from how it's used
+    // in the body (see `infer` and the `Call` arm of `interpret_ast`).
     let args = args
         .iter()
-        .map(|arg| {
-            arg.as_identifier()
-                .map(|id| (id.name.clone(), ItpTypeValue::Float))
-        })
+        .map(|arg| arg.as_identifier().map(|id| (id.name.clone(), infer::fresh_var())))
         .collect::<Result<Vec<(String, ItpTypeValue)>>>()?;
 
     // TODO: Interpret body when scope isn't the global scope
@@ -56,7 +58,7 @@ fn fn_macro(
             variadic: false,
         },
         body: body.to_vec(),
-        return_type: ItpTypeValue::Float,
+        return_type: infer::fresh_var(),
     });
 
     let function = Rc::new(function);
@@ -135,6 +137,25 @@ fn if_macro(
         return Err(anyhow!("Expected single else"));
     }
 
+    let cond_ty = condition[0].get_type();
+    let mut subst = itpr.subst.borrow_mut();
+    if let Err((a, b)) = infer::unify(&mut subst, &cond_ty, &ItpTypeValue::Bool) {
+        drop(subst);
+        return Err(anyhow!("'@if' condition must be a Bool, got {:?} (expected {:?})", a, b));
+    }
+
+    let then_ty = then[0].get_type();
+    let else_ty = else_[0].get_type();
+    if let Err((a, b)) = infer::unify(&mut subst, &then_ty, &else_ty) {
+        drop(subst);
+        return Err(anyhow!(
+            "'@if' arms must have the same type, got {:?} and {:?}",
+            a,
+            b
+        ));
+    }
+    drop(subst);
+
     Ok(vec![ItpAst {
         kind: ItpAstKind::Conditional {
             condition: Box::new(condition[0].clone()),
@@ -146,6 +167,42 @@ fn if_macro(
     }])
 }
 
+/// (@struct $Name [field1, field2])
+///
+/// Registers `$Name`'s field layout under its (de-sigiled) name in `scope`,
+/// so `($Name ...)` can construct it and `field`/`set-field` can look up a
+/// field's position. Like `@fn`'s parameters, fields have no type
+/// annotation syntax yet, so each gets a fresh type variable resolved from
+/// how it's used at construction sites.
+fn struct_macro(
+    ast: &[ParsedAst],
+    scope: Rc<RefCell<Scope>>,
+    _itpr: &mut Interpreter,
+) -> Result<Vec<ItpAst>> {
+    let name = ast[0].as_identifier()?;
+    let fields = ast[1].as_array()?;
+
+    let fields = fields
+        .iter()
+        .map(|field| {
+            field
+                .as_identifier()
+                .map(|id| (id.name.clone(), infer::fresh_var()))
+        })
+        .collect::<Result<Vec<(String, ItpTypeValue)>>>()?;
+
+    let ty = ItpTypeValue::Struct {
+        name: name.name.clone(),
+        fields,
+    };
+
+    scope
+        .borrow_mut()
+        .set(name.name.clone(), Rc::new(ItpValue::Type(ty)))?;
+
+    Ok(vec![])
+}
+
 /// (@while condition body)
 fn while_macro(
     ast: &[ParsedAst],
@@ -3,22 +3,25 @@ use std::{cell::RefCell, collections::HashMap, rc::Rc};
 use anyhow::Result;
 
 use crate::{
+    diagnostics::{Diagnostic, Span},
     parser::ast::{ParsedAst, ParsedAstKind},
     tokens::IdentifierKind,
 };
 
 use self::{
     ast::{ItpAst, ItpAstKind},
+    infer::Substitution,
     macros::Macro,
     native_fns::add_native_fns,
     scope::Scope,
     value::{
-        IFPCheck, ItpConstantValue, ItpFunctionValue, ItpValue, NativeFunctionValue,
+        ItpConstantValue, ItpFunctionValue, ItpTypeValue, ItpValue, NativeFunctionValue,
         UnItpedFunctionValue,
     },
 };
 
 pub mod ast;
+pub mod infer;
 pub mod macros;
 pub mod native_fns;
 pub mod scope;
@@ -28,6 +31,15 @@ pub mod value;
 pub struct Interpreter {
     pub scope: Rc<RefCell<Scope>>,
     pub macros: HashMap<String, Macro>,
+    /// Type-variable bindings accumulated by unification as the program is
+    /// interpreted. Applied to every node's type once interpretation
+    /// finishes, see `infer::apply_subst_ast`.
+    pub subst: RefCell<Substitution>,
+    /// Problems found so far. Recoverable ones (e.g. an unknown variable)
+    /// are pushed here and swapped for an `ItpAstKind::Error` node rather
+    /// than aborting, so a single `interpret` call surfaces every problem
+    /// in the program instead of just the first.
+    pub diagnostics: RefCell<Vec<Diagnostic>>,
 }
 
 impl Interpreter {
@@ -35,11 +47,257 @@ impl Interpreter {
         let mut me = Interpreter {
             scope: Rc::new(RefCell::new(Scope::new())),
             macros: macros::macros(),
+            subst: RefCell::new(Substitution::new()),
+            diagnostics: RefCell::new(vec![]),
         };
         add_native_fns(&mut me);
         me
     }
 
+    /// Records a recoverable problem and returns the `Error` placeholder
+    /// node that should stand in for whatever failed to interpret.
+    fn error_node(&self, line: usize, column: usize, message: impl Into<String>) -> ItpAst {
+        self.diagnostics
+            .borrow_mut()
+            .push(Diagnostic::error(message, Span::new(line, column)));
+
+        ItpAst {
+            kind: ItpAstKind::Error,
+            line,
+            column,
+        }
+    }
+
+    /// Builds an `Index` node for `value[index]`/`(get value index)`. A
+    /// constant integer index into an `Array` literal, or into a
+    /// known-length `Array` type, resolves to that element's own type
+    /// directly - the bounds check has already happened right here, so
+    /// there's nothing left to fault at runtime. Any other `Array` access
+    /// (an unsized array, or a non-constant index) can't be proven safe
+    /// ahead of time, so it yields `Option<element>` instead (see the
+    /// `ItpAstKind::Index` codegen arm for how `none` gets produced there)
+    /// rather than trapping the way it used to. A `Tuple` (see
+    /// `ItpTypeValue::Tuple`) has no shared element type to fall back on, so
+    /// it only ever accepts a constant index, checked against its arity
+    /// right here.
+    fn build_index(&self, line: usize, column: usize, value: ItpAst, index: ItpAst) -> ItpAst {
+        let result = match (&value.kind, &index.kind) {
+            (
+                ItpAstKind::Array(elements),
+                ItpAstKind::Constant {
+                    value: ItpConstantValue::Int(i),
+                    ..
+                },
+            ) => {
+                match usize::try_from(*i).ok().and_then(|i| elements.get(i)) {
+                    Some(element) => element.get_type(),
+                    None => {
+                        return self.error_node(
+                            line,
+                            column,
+                            format!("Index {} out of bounds for a {}-element array", i, elements.len()),
+                        )
+                    }
+                }
+            }
+            (
+                ItpAstKind::Tuple(elements),
+                ItpAstKind::Constant {
+                    value: ItpConstantValue::Int(i),
+                    ..
+                },
+            ) => {
+                match usize::try_from(*i).ok().and_then(|i| elements.get(i)) {
+                    Some(element) => element.get_type(),
+                    None => {
+                        return self.error_node(
+                            line,
+                            column,
+                            format!("Index {} out of bounds for a {}-element tuple", i, elements.len()),
+                        )
+                    }
+                }
+            }
+            _ => match value.get_type() {
+                // A known-length `Array` (e.g. a variable bound to one)
+                // indexed by a constant gets the same compile-time bounds
+                // check as an inline literal, so it's still proven safe and
+                // yields the element type directly. Anything else - an
+                // unsized array (e.g. the `get` native fn's own parameter)
+                // or a non-constant index - can actually go out of bounds at
+                // runtime, so it yields `Option<element>` instead (see the
+                // `ItpAstKind::Index` codegen arm for how that's populated)
+                // rather than trapping.
+                ItpTypeValue::Array { element, length } => match (length, &index.kind) {
+                    (
+                        Some(length),
+                        ItpAstKind::Constant {
+                            value: ItpConstantValue::Int(i),
+                            ..
+                        },
+                    ) => {
+                        if usize::try_from(*i).ok().filter(|i| *i < length).is_none() {
+                            return self.error_node(
+                                line,
+                                column,
+                                format!("Index {} out of bounds for a {}-element array", i, length),
+                            );
+                        }
+                        *element
+                    }
+                    _ => ItpTypeValue::Option(element),
+                },
+                // A `Tuple` behind a variable (e.g. a parameter) has no
+                // runtime-indexable form, so the index must still be a
+                // constant here, the same as an inline `Tuple` literal above.
+                ItpTypeValue::Tuple(types) => {
+                    let ItpAstKind::Constant {
+                        value: ItpConstantValue::Int(i),
+                        ..
+                    } = &index.kind
+                    else {
+                        return self.error_node(line, column, "Tuple index must be a constant integer");
+                    };
+                    match usize::try_from(*i).ok().and_then(|i| types.get(i)) {
+                        Some(ty) => ty.clone(),
+                        None => {
+                            return self.error_node(
+                                line,
+                                column,
+                                format!("Index {} out of bounds for a {}-element tuple", i, types.len()),
+                            )
+                        }
+                    }
+                }
+                other => {
+                    return self.error_node(
+                        line,
+                        column,
+                        format!("Cannot index into a value of type {:?}", other),
+                    )
+                }
+            },
+        };
+
+        ItpAst {
+            kind: ItpAstKind::Index {
+                value: Box::new(value),
+                index: Box::new(index),
+                result,
+            },
+            line,
+            column,
+        }
+    }
+
+    /// Looks `field` up by name in `value`'s struct type, erroring if
+    /// `value` isn't a struct or has no such field. Returns the field's
+    /// type; its position (needed for `build_struct_gep`) is re-resolved
+    /// by name at codegen time instead of being carried on the node.
+    fn resolve_field(
+        &self,
+        line: usize,
+        column: usize,
+        value: &ItpAst,
+        field: &str,
+    ) -> Result<ItpTypeValue, ItpAst> {
+        match value.get_type() {
+            ItpTypeValue::Struct { fields, .. } => {
+                match fields.iter().find(|(name, _)| name == field) {
+                    Some((_, ty)) => Ok(ty.clone()),
+                    None => Err(self.error_node(
+                        line,
+                        column,
+                        format!("No field named {} on this struct", field),
+                    )),
+                }
+            }
+            other => Err(self.error_node(
+                line,
+                column,
+                format!("Cannot access a field on a value of type {:?}", other),
+            )),
+        }
+    }
+
+    /// Builds a `Field` node for `(field value "name")`. `field` must be a
+    /// string literal, since the field's position has to be known to emit
+    /// `build_struct_gep` rather than being resolved at runtime.
+    fn build_field(&self, line: usize, column: usize, value: ItpAst, field: ItpAst) -> ItpAst {
+        let ItpAstKind::Constant {
+            value: ItpConstantValue::String(field_name),
+            ..
+        } = &field.kind
+        else {
+            return self.error_node(line, column, "Field name must be a string literal");
+        };
+        let field_name = field_name.clone();
+
+        let result = match self.resolve_field(line, column, &value, &field_name) {
+            Ok(ty) => ty,
+            Err(error_node) => return error_node,
+        };
+
+        ItpAst {
+            kind: ItpAstKind::Field {
+                value: Box::new(value),
+                field: field_name,
+                result,
+            },
+            line,
+            column,
+        }
+    }
+
+    /// Builds a `SetField` node for `(set-field value "name" new_value)`.
+    fn build_set_field(
+        &self,
+        line: usize,
+        column: usize,
+        value: ItpAst,
+        field: ItpAst,
+        new_value: ItpAst,
+    ) -> ItpAst {
+        let ItpAstKind::Constant {
+            value: ItpConstantValue::String(field_name),
+            ..
+        } = &field.kind
+        else {
+            return self.error_node(line, column, "Field name must be a string literal");
+        };
+        let field_name = field_name.clone();
+
+        let expected = match self.resolve_field(line, column, &value, &field_name) {
+            Ok(ty) => ty,
+            Err(error_node) => return error_node,
+        };
+
+        let got = new_value.get_type();
+        let mut subst = self.subst.borrow_mut();
+        if let Err((a, b)) = infer::unify(&mut subst, &got, &expected) {
+            drop(subst);
+            return self.error_node(
+                line,
+                column,
+                format!(
+                    "Wrong type for field {}: got {:?}, expected {:?}",
+                    field_name, a, b
+                ),
+            );
+        }
+        drop(subst);
+
+        ItpAst {
+            kind: ItpAstKind::SetField {
+                value: Box::new(value),
+                field: field_name,
+                new_value: Box::new(new_value),
+            },
+            line,
+            column,
+        }
+    }
+
     fn interpret_ast(
         &mut self,
         ast: &ParsedAst,
@@ -48,47 +306,152 @@ impl Interpreter {
         let line = ast.line;
         let column = ast.column;
         match &ast.kind {
+            // An integer literal's own type starts out as a fresh
+            // `NumericVar` rather than the fixed `Int(IntWidth::I64)` that
+            // `ItpConstantValue::Int::get_type()` would give it, so e.g.
+            // `42` can still resolve to `Float` if that's how it's used
+            // (see `infer::unify` and `infer::apply_subst_ast`).
             ParsedAstKind::Int(value) => Ok(vec![ItpAst {
-                kind: ItpAstKind::Constant(ItpConstantValue::Int(*value)),
+                kind: ItpAstKind::Constant {
+                    value: ItpConstantValue::Int(*value),
+                    result: infer::fresh_numeric_var(),
+                },
                 line,
                 column,
             }]),
             ParsedAstKind::Float(value) => Ok(vec![ItpAst {
-                kind: ItpAstKind::Constant(ItpConstantValue::Float(*value)),
+                kind: ItpAstKind::Constant {
+                    value: ItpConstantValue::Float(*value),
+                    result: ItpTypeValue::Float,
+                },
                 line,
                 column,
             }]),
             ParsedAstKind::String(value) => Ok(vec![ItpAst {
-                kind: ItpAstKind::Constant(ItpConstantValue::String(value.clone())),
+                kind: ItpAstKind::Constant {
+                    value: ItpConstantValue::String(value.clone()),
+                    result: ItpTypeValue::String,
+                },
                 line,
                 column,
             }]),
             ParsedAstKind::Char(value) => Ok(vec![ItpAst {
-                kind: ItpAstKind::Constant(ItpConstantValue::Char(*value)),
+                kind: ItpAstKind::Constant {
+                    value: ItpConstantValue::Char(*value),
+                    result: ItpTypeValue::Char,
+                },
                 line,
                 column,
             }]),
             ParsedAstKind::Bool(value) => Ok(vec![ItpAst {
-                kind: ItpAstKind::Constant(ItpConstantValue::Bool(*value)),
+                kind: ItpAstKind::Constant {
+                    value: ItpConstantValue::Bool(*value),
+                    result: ItpTypeValue::Bool,
+                },
                 line,
                 column,
             }]),
             ParsedAstKind::Array(values) => {
-                let mut result = vec![];
+                let mut elements = vec![];
                 for value in values {
-                    result.extend(self.interpret_ast(value, scope)?);
+                    elements.extend(self.interpret_ast(value, scope)?);
                 }
-                Ok(result)
+
+                let element_ty = infer::fresh_var();
+                let mut subst = self.subst.borrow_mut();
+                for element in &elements {
+                    let got = element.get_type();
+                    if let Err((a, b)) = infer::unify(&mut subst, &got, &element_ty) {
+                        drop(subst);
+                        return Ok(vec![self.error_node(
+                            line,
+                            column,
+                            format!(
+                                "Array elements must all have the same type, got {:?} and {:?}",
+                                a, b
+                            ),
+                        )]);
+                    }
+                }
+                drop(subst);
+
+                Ok(vec![ItpAst {
+                    kind: ItpAstKind::Array(elements),
+                    line,
+                    column,
+                }])
+            }
+            ParsedAstKind::Tuple(values) => {
+                let mut elements = vec![];
+                for value in values {
+                    elements.extend(self.interpret_ast(value, scope)?);
+                }
+                Ok(vec![ItpAst {
+                    kind: ItpAstKind::Tuple(elements),
+                    line,
+                    column,
+                }])
             }
+            // `Parser::parse_expression` only builds these so far; turning
+            // them into something `Interpreter` actually evaluates is
+            // follow-up work, so for now they're a recoverable diagnostic
+            // like an unknown variable rather than a hard parse failure.
+            ParsedAstKind::Binary { .. } => Ok(vec![self.error_node(
+                line,
+                column,
+                "Binary expressions are not yet supported by the interpreter",
+            )]),
+            ParsedAstKind::Unary { .. } => Ok(vec![self.error_node(
+                line,
+                column,
+                "Unary expressions are not yet supported by the interpreter",
+            )]),
+            ParsedAstKind::If { .. } => Ok(vec![self.error_node(
+                line,
+                column,
+                "`if` expressions are not yet supported by the interpreter",
+            )]),
+            ParsedAstKind::Let { .. } => Ok(vec![self.error_node(
+                line,
+                column,
+                "`let` bindings are not yet supported by the interpreter",
+            )]),
+            ParsedAstKind::While { .. } => Ok(vec![self.error_node(
+                line,
+                column,
+                "`while` loops are not yet supported by the interpreter",
+            )]),
+            ParsedAstKind::Block(_) => Ok(vec![self.error_node(
+                line,
+                column,
+                "Block expressions are not yet supported by the interpreter",
+            )]),
+            ParsedAstKind::Index { .. } => Ok(vec![self.error_node(
+                line,
+                column,
+                "Postfix `base[index]` expressions are not yet supported by the interpreter",
+            )]),
+            ParsedAstKind::Field { .. } => Ok(vec![self.error_node(
+                line,
+                column,
+                "Postfix `base.field` expressions are not yet supported by the interpreter",
+            )]),
             ParsedAstKind::Identifier(identifier) => match identifier.kind {
                 IdentifierKind::Variable => {
                     let scope = scope.borrow();
-                    let value = scope.get(&identifier.name).ok_or_else(|| {
-                        ast.error(&format!("Variable {} not found", identifier.name))
-                    })?;
+                    let Some(value) = scope.get(&identifier.name) else {
+                        return Ok(vec![self.error_node(
+                            line,
+                            column,
+                            format!("Variable {} not found", identifier.name),
+                        )]);
+                    };
                     match value.as_ref() {
                         ItpValue::Constant(c) => Ok(vec![ItpAst {
-                            kind: ItpAstKind::Constant(c.clone()),
+                            kind: ItpAstKind::Constant {
+                                result: c.get_type(),
+                                value: c.clone(),
+                            },
                             line,
                             column,
                         }]),
@@ -124,10 +487,14 @@ impl Interpreter {
                     macro_(args, self)
                 }
                 IdentifierKind::Variable => {
-                    let func = scope
-                        .borrow()
-                        .get(&name.name)
-                        .ok_or_else(|| ast.error(&format!("Function {} not found", name.name)))?;
+                    let func = scope.borrow().get(&name.name);
+                    let Some(func) = func else {
+                        return Ok(vec![self.error_node(
+                            line,
+                            column,
+                            format!("Function {} not found", name.name),
+                        )]);
+                    };
 
                     match func.as_ref() {
                         ItpValue::Function(ItpFunctionValue {
@@ -152,23 +519,65 @@ impl Interpreter {
                                 result.extend(self.interpret_ast(arg, &new_scope)?);
                             }
 
-                            match parameters
-                                .check_params(&result.iter().map(|a| a.get_type()).collect())
-                            {
-                                IFPCheck::Ok => Ok(()),
-                                IFPCheck::NotEnoughParameters => ast.err("Not enough parameters"),
-                                IFPCheck::TooManyParameters => ast.err("Too many parameters"),
-                                IFPCheck::WrongType(i, got, expected) => ast.err(&format!(
-                                    "Wrong type for parameter {}: got {:?}, expected {:?}",
-                                    i, got, expected
-                                )),
-                            }?;
+                            if name.name == "get" && result.len() == 2 {
+                                return Ok(vec![self.build_index(
+                                    line,
+                                    column,
+                                    result.remove(0),
+                                    result.remove(0),
+                                )]);
+                            }
+
+                            if name.name == "field" && result.len() == 2 {
+                                return Ok(vec![self.build_field(
+                                    line,
+                                    column,
+                                    result.remove(0),
+                                    result.remove(0),
+                                )]);
+                            }
+
+                            if name.name == "set-field" && result.len() == 3 {
+                                return Ok(vec![self.build_set_field(
+                                    line,
+                                    column,
+                                    result.remove(0),
+                                    result.remove(0),
+                                    result.remove(0),
+                                )]);
+                            }
+
+                            if result.len() < parameters.parameters.len() {
+                                return Ok(vec![self.error_node(line, column, "Not enough parameters")]);
+                            }
+                            if !parameters.variadic && result.len() > parameters.parameters.len() {
+                                return Ok(vec![self.error_node(line, column, "Too many parameters")]);
+                            }
+
+                            let (expected, generics) = parameters.instantiate();
+                            let mut subst = self.subst.borrow_mut();
+                            for (i, expected) in expected.iter().enumerate() {
+                                let got = result[i].get_type();
+                                if let Err((a, b)) = infer::unify(&mut subst, &got, expected) {
+                                    drop(subst);
+                                    return Ok(vec![self.error_node(
+                                        line,
+                                        column,
+                                        format!(
+                                            "Wrong type for parameter {}: got {:?}, expected {:?}",
+                                            i, a, b
+                                        ),
+                                    )]);
+                                }
+                            }
+                            let result_type = return_type.replace_generics(&generics);
+                            drop(subst);
 
                             Ok(vec![ItpAst {
                                 kind: ItpAstKind::Call {
                                     function: name.clone(),
                                     arguments: result,
-                                    result: return_type.clone(),
+                                    result: result_type,
                                 },
                                 line,
                                 column,
@@ -177,7 +586,84 @@ impl Interpreter {
                         _ => ast.err(&format!("{} is not a function", name.name)),
                     }
                 }
-                IdentifierKind::Type => ast.err("Type not allowed here"),
+                // `($Name arg1 arg2 ...)`: calling a struct's type
+                // identifier constructs an instance, in field-declaration
+                // order (see `struct_macro`).
+                IdentifierKind::Type => {
+                    let ty = scope.borrow().get(&name.name);
+                    let Some(ty) = ty else {
+                        return Ok(vec![self.error_node(
+                            line,
+                            column,
+                            format!("Type {} not found", name.name),
+                        )]);
+                    };
+                    let ItpValue::Type(ItpTypeValue::Struct {
+                        name: struct_name,
+                        fields,
+                    }) = ty.as_ref()
+                    else {
+                        return Ok(vec![self.error_node(
+                            line,
+                            column,
+                            format!("{} is not a struct type", name.name),
+                        )]);
+                    };
+
+                    if args.len() != fields.len() {
+                        return Ok(vec![self.error_node(
+                            line,
+                            column,
+                            format!(
+                                "Expected {} fields for {}, got {}",
+                                fields.len(),
+                                struct_name,
+                                args.len()
+                            ),
+                        )]);
+                    }
+
+                    let struct_name = struct_name.clone();
+                    let fields = fields.clone();
+                    let mut built_fields = vec![];
+                    for (arg, (field_name, field_ty)) in args.iter().zip(fields.iter()) {
+                        let value = self.interpret_ast(arg, scope)?;
+                        if value.len() != 1 {
+                            return Ok(vec![self.error_node(
+                                line,
+                                column,
+                                format!("Expected a single value for field {}", field_name),
+                            )]);
+                        }
+                        let value = value.into_iter().next().unwrap();
+
+                        let got = value.get_type();
+                        let mut subst = self.subst.borrow_mut();
+                        if let Err((a, b)) = infer::unify(&mut subst, &got, field_ty) {
+                            drop(subst);
+                            return Ok(vec![self.error_node(
+                                line,
+                                column,
+                                format!(
+                                    "Wrong type for field {}: got {:?}, expected {:?}",
+                                    field_name, a, b
+                                ),
+                            )]);
+                        }
+                        drop(subst);
+
+                        built_fields.push((field_name.clone(), value));
+                    }
+
+                    Ok(vec![ItpAst {
+                        kind: ItpAstKind::Struct {
+                            name: struct_name,
+                            fields: built_fields,
+                        },
+                        line,
+                        column,
+                    }])
+                }
             },
         }
     }
@@ -202,18 +688,75 @@ impl Interpreter {
                 }
 
                 let mut interpreted_body = vec![];
+                let mut body_failed = false;
 
                 for ast in body {
-                    interpreted_body.extend(self.interpret_ast(ast, &new_scope)?);
+                    match self.interpret_ast(ast, &new_scope) {
+                        Ok(nodes) => interpreted_body.extend(nodes),
+                        Err(err) => {
+                            // A hard error (e.g. a malformed macro call) in
+                            // this function's body shouldn't stop us from
+                            // still checking every other function.
+                            self.diagnostics.borrow_mut().push(Diagnostic::error(
+                                err.to_string(),
+                                Span::new(ast.line, ast.column),
+                            ));
+                            body_failed = true;
+                            break;
+                        }
+                    }
                 }
 
+                if body_failed {
+                    continue;
+                }
+
+                if let Some(last) = interpreted_body.last() {
+                    let mut subst = self.subst.borrow_mut();
+                    if let Err((a, b)) = infer::unify(&mut subst, &last.get_type(), return_type) {
+                        drop(subst);
+                        self.diagnostics.borrow_mut().push(Diagnostic::error(
+                            format!(
+                                "Function {} returns {:?} but is declared to return {:?}",
+                                fn_name, a, b
+                            ),
+                            Span::new(last.line, last.column),
+                        ));
+                    }
+                }
+
+                // Generalize this function's own free type variables into a
+                // scheme: any parameter/return type still unbound after
+                // checking the body becomes a named generic, so each call
+                // site instantiates its own fresh copy via
+                // `ItpFunctionParameters::instantiate` instead of every
+                // caller being forced to agree on one concrete type (e.g.
+                // `(@fn id [x] x)` works at multiple types).
+                //
+                // NOTE: a sibling `@fn` interpreted in this same batch
+                // still sees this function's un-generalized shape, since
+                // the generalized version isn't written back to `scope`
+                // until the batch completes below. Polymorphism across
+                // mutually-calling functions defined in the same batch is
+                // a known limitation.
+                let subst_snapshot = self.subst.borrow().clone();
+                let mut parameters = parameters.clone();
+                let mut return_type = return_type.clone();
+                let mut targets: Vec<&mut ItpTypeValue> = parameters
+                    .parameters
+                    .iter_mut()
+                    .map(|(_, ty)| ty)
+                    .collect();
+                targets.push(&mut return_type);
+                parameters.generics = infer::generalize(&subst_snapshot, &mut targets);
+
                 new_functions.insert(
                     name.clone(),
                     ItpValue::Function(ItpFunctionValue {
                         name: fn_name.clone(),
-                        parameters: parameters.clone(),
+                        parameters,
                         body: interpreted_body,
-                        return_type: return_type.clone(),
+                        return_type,
                     }),
                 );
             }
@@ -226,13 +769,63 @@ impl Interpreter {
         Ok(())
     }
 
-    pub fn interpret(&mut self, ast: &Vec<ParsedAst>) -> Result<()> {
+    /// Interprets every top-level form, then every `@fn` body, accumulating
+    /// diagnostics instead of bailing on the first problem: a form that
+    /// hard-errors (a malformed macro call, say) is recorded and skipped so
+    /// the rest of the program is still checked. Returns every diagnostic
+    /// collected along the way; the caller decides whether any `Error`
+    /// severity entries should stop it from going on to codegen.
+    pub fn interpret(&mut self, ast: &Vec<ParsedAst>) -> Vec<Diagnostic> {
         for ast in ast {
-            self.interpret_ast(ast, &self.scope.clone())?;
+            if let Err(err) = self.interpret_ast(ast, &self.scope.clone()) {
+                self.diagnostics
+                    .borrow_mut()
+                    .push(Diagnostic::error(err.to_string(), Span::new(ast.line, ast.column)));
+            }
         }
 
-        self.interpret_uninterpreted_functions()?;
+        if let Err(err) = self.interpret_uninterpreted_functions() {
+            self.diagnostics
+                .borrow_mut()
+                .push(Diagnostic::error(err.to_string(), Span::new(0, 0)));
+        }
 
-        Ok(())
+        // Now that every call site has contributed its unification
+        // constraints, resolve the leftover type variables on every
+        // function body so codegen sees fully concrete types.
+        let subst = self.subst.borrow().clone();
+        let names: Vec<String> = self
+            .scope
+            .borrow()
+            .bindings
+            .iter()
+            .filter(|(_, v)| matches!(v.as_ref(), ItpValue::Function(_)))
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        for name in names {
+            let value = self.scope.borrow().get(&name).unwrap();
+            if let ItpValue::Function(mut func) = value.as_ref().clone() {
+                let mut ok = true;
+                for statement in func.body.iter_mut() {
+                    if let Err(err) = infer::apply_subst_ast(&subst, statement) {
+                        self.diagnostics.borrow_mut().push(Diagnostic::error(
+                            err.to_string(),
+                            Span::new(statement.line, statement.column),
+                        ));
+                        ok = false;
+                        break;
+                    }
+                }
+                if ok {
+                    let _ = self
+                        .scope
+                        .borrow_mut()
+                        .replace(name, Rc::new(ItpValue::Function(func)));
+                }
+            }
+        }
+
+        self.diagnostics.borrow().clone()
     }
 }
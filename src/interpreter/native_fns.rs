@@ -1,7 +1,10 @@
 use std::rc::Rc;
 
 use super::{
-    value::{ItpFunctionParameters, ItpTypeValue, ItpValue::NativeFunction, NativeFunctionValue},
+    value::{
+        IntWidth, ItpFunctionParameters, ItpTypeValue, ItpValue::NativeFunction,
+        NativeFunctionValue,
+    },
     Interpreter,
 };
 
@@ -28,14 +31,14 @@ pub fn add_native_fns(itp: &mut Interpreter) {
         scope,
         "+",
         ItpFunctionParameters {
-            generics: vec![],
+            generics: vec!["T".to_string()],
             parameters: vec![
-                ("a".to_string(), ItpTypeValue::Float),
-                ("b".to_string(), ItpTypeValue::Float)
+                ("a".to_string(), ItpTypeValue::generic("T")),
+                ("b".to_string(), ItpTypeValue::generic("T"))
             ],
             variadic: false,
         },
-        ItpTypeValue::Float,
+        ItpTypeValue::generic("T"),
         true,
     );
 
@@ -43,14 +46,14 @@ pub fn add_native_fns(itp: &mut Interpreter) {
         scope,
         "-",
         ItpFunctionParameters {
-            generics: vec![],
+            generics: vec!["T".to_string()],
             parameters: vec![
-                ("a".to_string(), ItpTypeValue::Float),
-                ("b".to_string(), ItpTypeValue::Float)
+                ("a".to_string(), ItpTypeValue::generic("T")),
+                ("b".to_string(), ItpTypeValue::generic("T"))
             ],
             variadic: false,
         },
-        ItpTypeValue::Float,
+        ItpTypeValue::generic("T"),
         true,
     );
 
@@ -58,14 +61,14 @@ pub fn add_native_fns(itp: &mut Interpreter) {
         scope,
         "*",
         ItpFunctionParameters {
-            generics: vec![],
+            generics: vec!["T".to_string()],
             parameters: vec![
-                ("a".to_string(), ItpTypeValue::Float),
-                ("b".to_string(), ItpTypeValue::Float)
+                ("a".to_string(), ItpTypeValue::generic("T")),
+                ("b".to_string(), ItpTypeValue::generic("T"))
             ],
             variadic: false,
         },
-        ItpTypeValue::Float,
+        ItpTypeValue::generic("T"),
         true,
     );
 
@@ -73,14 +76,14 @@ pub fn add_native_fns(itp: &mut Interpreter) {
         scope,
         "/",
         ItpFunctionParameters {
-            generics: vec![],
+            generics: vec!["T".to_string()],
             parameters: vec![
-                ("a".to_string(), ItpTypeValue::Float),
-                ("b".to_string(), ItpTypeValue::Float)
+                ("a".to_string(), ItpTypeValue::generic("T")),
+                ("b".to_string(), ItpTypeValue::generic("T"))
             ],
             variadic: false,
         },
-        ItpTypeValue::Float,
+        ItpTypeValue::generic("T"),
         true,
     );
 
@@ -99,6 +102,87 @@ pub fn add_native_fns(itp: &mut Interpreter) {
         true,
     );
 
+    add_native_fn!(
+        scope,
+        "!=",
+        ItpFunctionParameters {
+            generics: vec!["T".to_string()],
+            parameters: vec![
+                ("a".to_string(), ItpTypeValue::generic("T")),
+                ("b".to_string(), ItpTypeValue::generic("T"))
+            ],
+            variadic: false,
+        },
+        ItpTypeValue::Bool,
+        true,
+    );
+
+    add_native_fn!(
+        scope,
+        "<",
+        ItpFunctionParameters {
+            generics: vec!["T".to_string()],
+            parameters: vec![
+                ("a".to_string(), ItpTypeValue::generic("T")),
+                ("b".to_string(), ItpTypeValue::generic("T"))
+            ],
+            variadic: false,
+        },
+        ItpTypeValue::Bool,
+        true,
+    );
+
+    add_native_fn!(
+        scope,
+        ">",
+        ItpFunctionParameters {
+            generics: vec!["T".to_string()],
+            parameters: vec![
+                ("a".to_string(), ItpTypeValue::generic("T")),
+                ("b".to_string(), ItpTypeValue::generic("T"))
+            ],
+            variadic: false,
+        },
+        ItpTypeValue::Bool,
+        true,
+    );
+
+    add_native_fn!(
+        scope,
+        "<=",
+        ItpFunctionParameters {
+            generics: vec!["T".to_string()],
+            parameters: vec![
+                ("a".to_string(), ItpTypeValue::generic("T")),
+                ("b".to_string(), ItpTypeValue::generic("T"))
+            ],
+            variadic: false,
+        },
+        ItpTypeValue::Bool,
+        true,
+    );
+
+    add_native_fn!(
+        scope,
+        ">=",
+        ItpFunctionParameters {
+            generics: vec!["T".to_string()],
+            parameters: vec![
+                ("a".to_string(), ItpTypeValue::generic("T")),
+                ("b".to_string(), ItpTypeValue::generic("T"))
+            ],
+            variadic: false,
+        },
+        ItpTypeValue::Bool,
+        true,
+    );
+
+    // The declared return type here is only documentation: `get` is
+    // special-cased in `interpret_ast` to go through `Interpreter::build_index`
+    // instead of this signature's own unification, since the precise result
+    // type depends on the arguments (a constant index into a known-length array
+    // is still proven safe and yields `T` directly; anything else yields
+    // `Option<T>`, see `build_index`'s doc comment).
     add_native_fn!(
         scope,
         "get",
@@ -107,12 +191,85 @@ pub fn add_native_fns(itp: &mut Interpreter) {
             parameters: vec![
                 (
                     "array".to_string(),
-                    ItpTypeValue::Array(Box::new(ItpTypeValue::generic("T")))
+                    ItpTypeValue::Array {
+                        element: Box::new(ItpTypeValue::generic("T")),
+                        length: None
+                    }
                 ),
-                ("index".to_string(), ItpTypeValue::Float)
+                ("index".to_string(), ItpTypeValue::Int(IntWidth::I64))
             ],
             variadic: false,
         },
+        ItpTypeValue::Option(Box::new(ItpTypeValue::generic("T"))),
+        true,
+    );
+
+    add_native_fn!(
+        scope,
+        "field",
+        ItpFunctionParameters {
+            generics: vec!["S".to_string(), "T".to_string()],
+            parameters: vec![
+                ("value".to_string(), ItpTypeValue::generic("S")),
+                ("name".to_string(), ItpTypeValue::String)
+            ],
+            variadic: false,
+        },
+        ItpTypeValue::generic("T"),
+        true,
+    );
+
+    add_native_fn!(
+        scope,
+        "set-field",
+        ItpFunctionParameters {
+            generics: vec!["S".to_string(), "T".to_string()],
+            parameters: vec![
+                ("value".to_string(), ItpTypeValue::generic("S")),
+                ("name".to_string(), ItpTypeValue::String),
+                ("new_value".to_string(), ItpTypeValue::generic("T"))
+            ],
+            variadic: false,
+        },
+        ItpTypeValue::Void,
+        true,
+    );
+
+    add_native_fn!(
+        scope,
+        "some",
+        ItpFunctionParameters {
+            generics: vec!["T".to_string()],
+            parameters: vec![("value".to_string(), ItpTypeValue::generic("T"))],
+            variadic: false,
+        },
+        ItpTypeValue::Option(Box::new(ItpTypeValue::generic("T"))),
+        true,
+    );
+
+    add_native_fn!(
+        scope,
+        "none",
+        ItpFunctionParameters {
+            generics: vec!["T".to_string()],
+            parameters: vec![],
+            variadic: false,
+        },
+        ItpTypeValue::Option(Box::new(ItpTypeValue::generic("T"))),
+        true,
+    );
+
+    add_native_fn!(
+        scope,
+        "unwrap",
+        ItpFunctionParameters {
+            generics: vec!["T".to_string()],
+            parameters: vec![(
+                "option".to_string(),
+                ItpTypeValue::Option(Box::new(ItpTypeValue::generic("T")))
+            )],
+            variadic: false,
+        },
         ItpTypeValue::generic("T"),
         true,
     );
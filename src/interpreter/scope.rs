@@ -1,13 +1,35 @@
-use super::value::ItpValue;
+use super::value::{ItpTypeValue, ItpValue};
 use anyhow::{anyhow, Result};
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::fmt::Debug;
 use std::rc::Rc;
 
+/// A host-provided source of names that no `@fn`/`@set` macro ever `set` in
+/// a `Scope` - built-ins, externs, or symbols defined in another compilation
+/// unit. Only the root `Scope` (the one with no `parent`) consults one,
+/// after its own bindings come up empty, so lexical bindings stay a closed
+/// `HashMap` chain and the ambient global environment is just one more
+/// pluggable link at the end of it.
+pub trait SymbolResolver: Debug {
+    /// The type a name would have if it were bound, without requiring the
+    /// resolver to construct a real value for it - e.g. an extern declared
+    /// by signature only, with no `ItpValue` to hand back until link time.
+    fn resolve_type(&self, name: &str) -> Option<ItpTypeValue>;
+
+    /// The value a name is actually bound to, for names the resolver can
+    /// produce a concrete `ItpValue` for (native/foreign function IDs,
+    /// constants injected by the host).
+    fn resolve_value(&self, name: &str) -> Option<Rc<ItpValue>>;
+}
+
 #[derive(Debug)]
 pub struct Scope {
     pub parent: Option<Rc<RefCell<Scope>>>,
     pub bindings: HashMap<String, Rc<ItpValue>>,
+    /// Consulted only when `parent` is `None`, i.e. only from the root
+    /// scope - see `SymbolResolver`.
+    pub resolver: Option<Rc<dyn SymbolResolver>>,
 }
 
 impl Scope {
@@ -15,6 +37,15 @@ impl Scope {
         Scope {
             parent: None,
             bindings: HashMap::new(),
+            resolver: None,
+        }
+    }
+
+    pub fn new_with_resolver(resolver: Rc<dyn SymbolResolver>) -> Self {
+        Scope {
+            parent: None,
+            bindings: HashMap::new(),
+            resolver: Some(resolver),
         }
     }
 
@@ -22,6 +53,7 @@ impl Scope {
         Scope {
             parent: Some(parent),
             bindings: HashMap::new(),
+            resolver: None,
         }
     }
 
@@ -34,7 +66,25 @@ impl Scope {
             return parent.borrow().get(name);
         }
 
-        None
+        self.resolver.as_ref()?.resolve_value(name)
+    }
+
+    /// Like `get`, but also answers for names the resolver only knows the
+    /// type of (e.g. an extern with no materialized value yet).
+    pub fn get_type(&self, name: &str) -> Option<ItpTypeValue> {
+        if let Some(value) = self.bindings.get(name) {
+            return Some(value.get_type());
+        }
+
+        if let Some(parent) = &self.parent {
+            return parent.borrow().get_type(name);
+        }
+
+        let resolver = self.resolver.as_ref()?;
+        resolver
+            .resolve_value(name)
+            .map(|value| value.get_type())
+            .or_else(|| resolver.resolve_type(name))
     }
 
     pub fn set(&mut self, name: String, value: Rc<ItpValue>) -> Result<()> {
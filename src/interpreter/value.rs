@@ -9,7 +9,9 @@ pub enum ItpValue {
     Param(u32, ItpTypeValue),
     Constant(ItpConstantValue),
     Named(Identifier, ItpTypeValue),
-    // Type(TypeValue),
+    /// A named layout registered by `@struct` (looked up by its `$Name`
+    /// identifier), rather than a value that exists at runtime.
+    Type(ItpTypeValue),
     Function(ItpFunctionValue),
     UnItpedFunction(UnItpedFunctionValue),
     NativeFunction(NativeFunctionValue),
@@ -21,6 +23,7 @@ impl ItpValue {
             ItpValue::Param(_, t) => t.clone(),
             ItpValue::Constant(c) => c.get_type(),
             ItpValue::Named(_, t) => t.clone(),
+            ItpValue::Type(t) => t.clone(),
             ItpValue::Function(f) => ItpTypeValue::Function {
                 parameters: f.parameters.clone(),
                 return_type: Box::new(f.return_type.clone()),
@@ -45,12 +48,19 @@ pub enum ItpConstantValue {
     Char(char),
     Bool(bool),
     Array(Vec<ItpValue>),
+    /// A `{a, b, c}` literal - unlike `Array`, elements may have different
+    /// types, so `get_type` doesn't have to unify them into one.
+    Tuple(Vec<ItpValue>),
+    /// A constant-folded `some(x)`/`none`. `None` still carries the inner
+    /// type, since `none` has no payload to read it back from.
+    Option(ItpTypeValue, Option<Box<ItpConstantValue>>),
+    Struct(String, Vec<(String, ItpConstantValue)>),
 }
 
 impl ItpConstantValue {
     pub fn get_type(&self) -> ItpTypeValue {
         match self {
-            ItpConstantValue::Int(_) => ItpTypeValue::Int,
+            ItpConstantValue::Int(_) => ItpTypeValue::Int(IntWidth::I64),
             ItpConstantValue::Float(_) => ItpTypeValue::Float,
             ItpConstantValue::String(_) => ItpTypeValue::String,
             ItpConstantValue::Char(_) => ItpTypeValue::Char,
@@ -59,29 +69,105 @@ impl ItpConstantValue {
                 let mut types = values.iter().map(|v| v.get_type()).collect::<HashSet<_>>();
 
                 if types.len() == 1 {
-                    ItpTypeValue::Array(Box::new(types.drain().next().unwrap()))
+                    ItpTypeValue::Array {
+                        element: Box::new(types.drain().next().unwrap()),
+                        length: Some(values.len()),
+                    }
                 } else {
                     panic!("Array with different types")
                 }
             }
+            ItpConstantValue::Tuple(values) => {
+                ItpTypeValue::Tuple(values.iter().map(|v| v.get_type()).collect())
+            }
+            ItpConstantValue::Option(inner, _) => ItpTypeValue::Option(Box::new(inner.clone())),
+            ItpConstantValue::Struct(name, fields) => ItpTypeValue::Struct {
+                name: name.clone(),
+                fields: fields
+                    .iter()
+                    .map(|(field, value)| (field.clone(), value.get_type()))
+                    .collect(),
+            },
         }
     }
 }
 
+/// Bit width and signedness of an integer type, e.g. `i32` is
+/// `IntWidth { bits: 32, signed: true }` and `u8` is
+/// `IntWidth { bits: 8, signed: false }`. LLVM integers themselves are
+/// signless, so this is what `check_intrinsic_fn` consults to pick
+/// `build_int_signed_div` vs `build_int_unsigned_div` and the like.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IntWidth {
+    pub bits: u32,
+    pub signed: bool,
+}
+
+impl IntWidth {
+    pub const I8: IntWidth = IntWidth { bits: 8, signed: true };
+    pub const I16: IntWidth = IntWidth { bits: 16, signed: true };
+    pub const I32: IntWidth = IntWidth { bits: 32, signed: true };
+    pub const I64: IntWidth = IntWidth { bits: 64, signed: true };
+    pub const U8: IntWidth = IntWidth { bits: 8, signed: false };
+    pub const U16: IntWidth = IntWidth { bits: 16, signed: false };
+    pub const U32: IntWidth = IntWidth { bits: 32, signed: false };
+    pub const U64: IntWidth = IntWidth { bits: 64, signed: false };
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ItpTypeValue {
-    Int,
+    Int(IntWidth),
     Float,
     String,
     Char,
     Bool,
-    Array(Box<ItpTypeValue>), // TODO: Absolutely should include length here
+    /// `length` is `None` for a generic/unsized array (e.g. the native
+    /// `get` function's `array` parameter, which accepts any length) and
+    /// `Some(n)` once it's known statically, e.g. an array literal's own
+    /// length. See
+    /// `infer::unify` for how a sized array unifies against an unsized one,
+    /// and `Interpreter::build_index` for the compile-time bounds check a
+    /// known length enables.
+    Array {
+        element: Box<ItpTypeValue>,
+        length: Option<usize>,
+    },
+    /// A fixed-arity, heterogeneous `{a, b, c}` literal's type - unlike
+    /// `Array`, each position can have its own type, so a *constant* index
+    /// into one (see `Interpreter::build_index`) projects out that exact
+    /// position's type instead of one shared element type.
+    Tuple(Vec<ItpTypeValue>),
+    Option(Box<ItpTypeValue>),
+    /// A named record type registered by `@struct`, lowered to an LLVM
+    /// struct (not a pointer to one) so nested structs and structs-in-arrays
+    /// embed directly; `field`/`set-field` look up `name` by position in
+    /// `fields` to get the `build_struct_gep` index.
+    Struct {
+        name: String,
+        fields: Vec<(String, ItpTypeValue)>,
+    },
     Function {
         parameters: ItpFunctionParameters,
         return_type: Box<ItpTypeValue>,
     },
     Generic(String),
+    /// A type-inference unification variable. Never appears in a node's
+    /// type once `infer::apply_subst_ast` has run over it.
+    Var(u32),
+    /// Like `Var`, but only ever produced for an integer literal's own
+    /// type, and only ever unifies against `Int`/`Float`/another
+    /// `NumericVar` (see `infer::unify`) - so `42` can flow into either an
+    /// `Int` or a `Float` context. If nothing constrains it by the time
+    /// `infer::finalize` runs, it defaults to `Int(IntWidth::I64)` rather
+    /// than erroring the way a genuinely unconstrained `Var` would.
+    NumericVar(u32),
     Void,
+    /// The bottom type: a function that never returns (always panics,
+    /// branches away, or loops forever) declares this as its return type.
+    /// Unifies with anything (see `infer::unify`), and a `Call` node whose
+    /// `result` resolves to `Never` is what `codegen::diverges` treats as
+    /// diverging control flow.
+    Never,
 }
 
 impl ItpTypeValue {
@@ -95,12 +181,34 @@ impl ItpTypeValue {
                 Some(t) => t.clone(),
                 None => self.clone(),
             },
-            ItpTypeValue::Array(t) => ItpTypeValue::Array(Box::new(t.replace_generics(generics))),
+            ItpTypeValue::Array { element, length } => ItpTypeValue::Array {
+                element: Box::new(element.replace_generics(generics)),
+                length: *length,
+            },
+            ItpTypeValue::Tuple(elements) => ItpTypeValue::Tuple(
+                elements.iter().map(|t| t.replace_generics(generics)).collect(),
+            ),
+            ItpTypeValue::Option(t) => ItpTypeValue::Option(Box::new(t.replace_generics(generics))),
+            ItpTypeValue::Struct { name, fields } => ItpTypeValue::Struct {
+                name: name.clone(),
+                fields: fields
+                    .iter()
+                    .map(|(field, t)| (field.clone(), t.replace_generics(generics)))
+                    .collect(),
+            },
             ItpTypeValue::Function {
                 parameters,
                 return_type,
             } => ItpTypeValue::Function {
-                parameters: parameters.clone(),
+                parameters: ItpFunctionParameters {
+                    generics: parameters.generics.clone(),
+                    parameters: parameters
+                        .parameters
+                        .iter()
+                        .map(|(name, t)| (name.clone(), t.replace_generics(generics)))
+                        .collect(),
+                    variadic: parameters.variadic,
+                },
                 return_type: Box::new(return_type.replace_generics(generics)),
             },
             _ => self.clone(),
@@ -115,67 +223,25 @@ pub struct ItpFunctionParameters {
     pub variadic: bool,
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum IFPCheck {
-    Ok(HashMap<String, ItpTypeValue>),
-    NotEnoughParameters,
-    TooManyParameters,
-    WrongType(usize, ItpTypeValue, ItpTypeValue),
-}
-
-fn check_param(
-    generics: &mut HashMap<String, ItpTypeValue>,
-    i: usize,
-    param: &ItpTypeValue,
-    value: &ItpTypeValue,
-) -> Result<(), IFPCheck> {
-    match param {
-        ItpTypeValue::Generic(name) => match generics.get(name) {
-            None => {
-                generics.insert(name.clone(), value.clone());
-                Ok(())
-            }
-            Some(g) => {
-                if g == value {
-                    Ok(())
-                } else {
-                    Err(IFPCheck::WrongType(i, param.clone(), value.clone()))
-                }
-            }
-        },
-        ItpTypeValue::Array(t) => match value {
-            ItpTypeValue::Array(v) => check_param(generics, i, t, v),
-            _ => Err(IFPCheck::WrongType(i, param.clone(), value.clone())),
-        },
-        _ => {
-            if param == value {
-                Ok(())
-            } else {
-                Err(IFPCheck::WrongType(i, param.clone(), value.clone()))
-            }
-        }
-    }
-}
-
 impl ItpFunctionParameters {
-    pub fn check_params(&self, params: &Vec<ItpTypeValue>) -> IFPCheck {
-        if params.len() < self.parameters.len() {
-            return IFPCheck::NotEnoughParameters;
-        }
-
-        if !self.variadic && params.len() > self.parameters.len() {
-            return IFPCheck::TooManyParameters;
-        }
+    /// Instantiates each declared generic with a fresh type variable,
+    /// yielding the concrete-shaped parameter/return types that a single
+    /// call site should unify its arguments against. See
+    /// `infer::unify` for how the resulting variables get solved.
+    pub fn instantiate(&self) -> (Vec<ItpTypeValue>, HashMap<String, ItpTypeValue>) {
+        let generics = self
+            .generics
+            .iter()
+            .map(|name| (name.clone(), super::infer::fresh_var()))
+            .collect::<HashMap<_, _>>();
 
-        let mut generics = HashMap::new();
-
-        for (i, (_, t)) in self.parameters.iter().enumerate() {
-            if let Err(r) = check_param(&mut generics, i, t, &params[i]) {
-                return r;
-            }
-        }
+        let parameters = self
+            .parameters
+            .iter()
+            .map(|(_, t)| t.replace_generics(&generics))
+            .collect();
 
-        IFPCheck::Ok(generics)
+        (parameters, generics)
     }
 }
 
@@ -28,6 +28,10 @@ impl Lexer {
         self.input.chars().nth(self.position)
     }
 
+    fn peek_char(&self) -> Option<char> {
+        self.input.chars().nth(self.position + 1)
+    }
+
     fn advance(&mut self) {
         if let Some(ch) = self.current_char() {
             if ch == '\n' {
@@ -77,44 +81,124 @@ impl Lexer {
         }
     }
 
-    fn read_number(&mut self) -> Result<Token> {
-        let mut number = String::new();
-        let mut float = false;
-        self.save_position();
+    /// Consumes a run of base-`radix` digits interleaved with `_`
+    /// separators, appending just the digits (not the separators) to
+    /// `into`. A `_` is only valid directly between two digits, never at
+    /// the start of the run, doubled up, or trailing.
+    fn read_digits(&mut self, radix: u32, into: &mut String) -> Result<()> {
+        let start = into.len();
+        let mut last_was_sep = false;
         while let Some(ch) = self.current_char() {
-            if ch.is_ascii_digit() || ch == '.' {
-                if ch == '.' {
-                    if float {
-                        return self.err_here("Unexpected '.' in number");
-                    }
-                    float = true;
+            if ch == '_' {
+                if into.len() == start || last_was_sep {
+                    return self.err_here("Unexpected '_' in number");
                 }
-                number.push(ch);
                 self.advance();
+                last_was_sep = true;
+            } else if ch.is_digit(radix) {
+                into.push(ch);
+                self.advance();
+                last_was_sep = false;
             } else {
                 break;
             }
         }
-        // TODO: Allow for more than just floats
-        // if float {
-        match number.parse::<f64>() {
-            Ok(f) => Ok(Token {
-                kind: TokenKind::Float(f),
+        if last_was_sep {
+            return self.err_here("Unexpected '_' in number");
+        }
+        Ok(())
+    }
+
+    /// Reads a `0x`/`0o`/`0b`-prefixed integer literal; `self.position` must
+    /// still be sitting on the leading `0`. Always an integer - a `.`
+    /// immediately after is a mistake, not the start of a fraction.
+    fn read_radix_int(&mut self, radix: u32) -> Result<Token> {
+        self.advance(); // '0'
+        self.advance(); // 'x' | 'o' | 'b'
+
+        let mut digits = String::new();
+        self.read_digits(radix, &mut digits)?;
+        if digits.is_empty() {
+            return self.err_here("Expected at least one digit after radix prefix");
+        }
+        if self.current_char() == Some('.') {
+            return self.err_here("A radix-prefixed integer cannot have a decimal point");
+        }
+
+        match i64::from_str_radix(&digits, radix) {
+            Ok(i) => Ok(Token {
+                kind: TokenKind::Int(i),
                 line: self.saved_line,
                 column: self.saved_column,
             }),
-            Err(_) => self.err("Invalid float"),
+            Err(_) => self.err("Invalid integer"),
+        }
+    }
+
+    fn read_number(&mut self) -> Result<Token> {
+        self.save_position();
+
+        if self.current_char() == Some('0') {
+            let radix = match self.peek_char() {
+                Some('x') | Some('X') => Some(16),
+                Some('o') | Some('O') => Some(8),
+                Some('b') | Some('B') => Some(2),
+                _ => None,
+            };
+            if let Some(radix) = radix {
+                return self.read_radix_int(radix);
+            }
+        }
+
+        let mut number = String::new();
+        self.read_digits(10, &mut number)?;
+
+        let mut float = false;
+        if self.current_char() == Some('.') {
+            float = true;
+            number.push('.');
+            self.advance();
+            self.read_digits(10, &mut number)?;
+        }
+        if self.current_char() == Some('.') {
+            return self.err_here("Unexpected '.' in number");
+        }
+
+        if matches!(self.current_char(), Some('e') | Some('E')) {
+            float = true;
+            number.push('e');
+            self.advance();
+            if let Some(sign @ ('+' | '-')) = self.current_char() {
+                number.push(sign);
+                self.advance();
+            }
+            let mut exponent = String::new();
+            self.read_digits(10, &mut exponent)?;
+            if exponent.is_empty() {
+                return self.err_here("Expected at least one digit in exponent");
+            }
+            number.push_str(&exponent);
+        }
+
+        if float {
+            match number.parse::<f64>() {
+                Ok(f) => Ok(Token {
+                    kind: TokenKind::Float(f),
+                    line: self.saved_line,
+                    column: self.saved_column,
+                }),
+                Err(_) => self.err("Invalid float"),
+            }
+        } else {
+            match number.parse::<i64>() {
+                Ok(i) => Ok(Token {
+                    kind: TokenKind::Int(i),
+                    line: self.saved_line,
+                    column: self.saved_column,
+                }),
+                Err(_) => self.err("Invalid integer"),
+            }
         }
-        // } else {
-        //     match number.parse::<i64>() {
-        //         Ok(i) => Ok(Token {
-        //             kind: TokenKind::Int(i),
-        //             line: self.saved_line,
-        //             column: self.saved_column,
-        //         }),
-        //         Err(_) => self.err("Invalid integer"),
-        //     }
-        // }
     }
 
     /// Reads just a single char that's part of a string or character literal.
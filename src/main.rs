@@ -2,12 +2,14 @@ use interpreter::Interpreter;
 use lexer::Lexer;
 use parser::Parser;
 
-use crate::codegen::{compile_to_file, compile_to_llvm_ir, run_jit};
+use crate::codegen::{compile_to_file, compile_to_llvm_ir, run_jit, CompileOptions};
 
 mod codegen;
+mod diagnostics;
 mod interpreter;
 mod lexer;
 mod parser;
+mod repl;
 mod tokens;
 
 fn main() {
@@ -23,16 +25,22 @@ fn main() {
     let tokens = Lexer::new(input.to_string()).lex().unwrap();
 
     let mut parser = Parser::new(tokens);
-    let ast = parser.parse().unwrap();
+    let (ast, errors) = parser.parse();
+    for error in &errors {
+        println!("{}", error.message());
+    }
 
     let mut interpreter = Interpreter::new();
-    interpreter.interpret(&ast).unwrap();
+    let diagnostics = interpreter.interpret(&ast);
+    for diagnostic in &diagnostics {
+        println!("{}", diagnostic.render(input));
+    }
 
     println!("=== LLVM IR ===");
-    let ir = compile_to_llvm_ir(&interpreter).unwrap();
+    let ir = compile_to_llvm_ir(&interpreter, input, CompileOptions::default()).unwrap();
     println!("{}", ir);
     println!("=== Writing to file ===");
-    compile_to_file(&interpreter, "hello").unwrap();
+    compile_to_file(&interpreter, input, "hello", CompileOptions::default()).unwrap();
     println!("=== Running JIT ===");
-    run_jit(&interpreter).unwrap();
+    run_jit(&interpreter, input, CompileOptions::default()).unwrap();
 }
@@ -95,9 +95,105 @@ pub enum ParsedAstKind {
     Char(char),
     String(String),
     Array(Vec<ParsedAst>),
+    /// A `{a, b, c}` tuple literal - unlike `Array`, its elements don't have
+    /// to share a type (see `ItpTypeValue::Tuple`).
+    Tuple(Vec<ParsedAst>),
     Identifier(Identifier),
     Call {
         name: Identifier,
         args: Vec<ParsedAst>,
     },
+    /// A binary operator expression built up by the precedence-climbing
+    /// parser in `Parser::parse_expression` - e.g. `1 + 2 * 3` folds into
+    /// `Binary("+", 1, Binary("*", 2, 3))` per its binding-power table.
+    Binary {
+        op: String,
+        left: Box<ParsedAst>,
+        right: Box<ParsedAst>,
+    },
+    /// A prefix operator (`-x`, `!x`), parsed with a binding power higher
+    /// than any infix operator so it binds tighter than whatever follows.
+    Unary {
+        op: String,
+        expr: Box<ParsedAst>,
+    },
+    /// `if cond { then } else { else_ }` - `else_` is `None` when the `else`
+    /// branch is omitted.
+    If {
+        cond: Box<ParsedAst>,
+        then: Box<ParsedAst>,
+        else_: Option<Box<ParsedAst>>,
+    },
+    /// `let name = value`.
+    Let {
+        name: Identifier,
+        value: Box<ParsedAst>,
+    },
+    /// `while cond { body }`.
+    While {
+        cond: Box<ParsedAst>,
+        body: Box<ParsedAst>,
+    },
+    /// A `{ expr; expr; ... }` sequence, as parsed by `Parser::parse_block` -
+    /// unlike `Tuple`, its elements aren't comma-separated and it isn't a
+    /// value-producing literal itself, just a grouping for `If`/`While`
+    /// bodies and `fn` item bodies.
+    Block(Vec<ParsedAst>),
+    /// `base[index]`, parsed by the postfix loop in `Parser::parse_postfix`.
+    Index {
+        base: Box<ParsedAst>,
+        index: Box<ParsedAst>,
+    },
+    /// `base.field`, parsed by the postfix loop in `Parser::parse_postfix`.
+    Field {
+        base: Box<ParsedAst>,
+        field: String,
+    },
+}
+
+/// A type annotation as written by the programmer, parsed by
+/// `Parser::parse_type`. This is parser-level only - nothing downstream
+/// resolves it into an `ItpTypeValue` yet, unlike the macro-declared
+/// functions/structs in `interpreter::macros`, whose parameters and fields
+/// get a fresh inferred type instead of an annotation.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Type {
+    /// A builtin type name, e.g. `int`, `float`, `bool`, `char`, `string`.
+    Builtin(String),
+    /// A named `@struct` type, e.g. `$Point`.
+    Named(String),
+    /// `* T` - a pointer to `T`. The `*` must be followed by whitespace,
+    /// since the lexer reads `*T` as a single identifier.
+    Pointer(Box<Type>),
+    /// `[T]` - an array of `T`.
+    Array(Box<Type>),
+}
+
+/// A single `name: Type` entry in a function's parameter list or a
+/// struct's field list.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TypedParam {
+    pub name: Identifier,
+    pub ty: Type,
+}
+
+/// A top-level declaration, as parsed by `Parser::parse_item`/`parse_items`
+/// - distinct from `ParsedAst`, which is only ever a value-producing
+/// expression (or, inside a function body, a statement within one).
+#[derive(Debug, PartialEq, Clone)]
+pub enum ParsedItem {
+    Function {
+        name: Identifier,
+        params: Vec<TypedParam>,
+        return_type: Type,
+        body: ParsedAst,
+        line: usize,
+        column: usize,
+    },
+    Struct {
+        name: Identifier,
+        fields: Vec<TypedParam>,
+        line: usize,
+        column: usize,
+    },
 }
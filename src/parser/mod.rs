@@ -1,8 +1,91 @@
-use super::tokens::{Token, TokenKind};
+use super::tokens::{Identifier, IdentifierKind, Token, TokenKind};
 use anyhow::{anyhow, Result};
-use ast::{ParsedAst, ParsedAstKind};
+use ast::{ParsedAst, ParsedAstKind, ParsedItem, Type, TypedParam};
 pub mod ast;
 
+/// A single parse failure recorded by `Parser::parse`'s recovery mode,
+/// instead of aborting the whole parse via `anyhow!`/`?` on the first one.
+/// Unlike `Diagnostic`, the message isn't built eagerly: `expected` keeps
+/// growing as `Parser::push_error` merges in more alternatives tried at the
+/// same position, so `message` renders the final, deduped set on demand.
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub expected: Vec<TokenKind>,
+    pub found: Token,
+}
+
+impl ParseError {
+    fn new(found: Token, expected: Vec<TokenKind>) -> Self {
+        Self {
+            line: found.line,
+            column: found.column,
+            expected,
+            found,
+        }
+    }
+
+    fn merge(&mut self, expected: Vec<TokenKind>) {
+        self.expected.extend(expected);
+    }
+
+    /// "expected `,` or `]`, found ...", deduping and sorting `expected` by
+    /// its rendered form first, so a position where several alternatives
+    /// were tried doesn't list one twice or in whatever order they happened
+    /// to run.
+    pub fn message(&self) -> String {
+        let mut rendered: Vec<String> = self
+            .expected
+            .iter()
+            .map(|kind| match kind {
+                TokenKind::Delimiter(c) => format!("`{}`", c),
+                other => other.to_string(),
+            })
+            .collect();
+        rendered.sort();
+        rendered.dedup();
+
+        let expected = match rendered.as_slice() {
+            [] => "an expression".to_string(),
+            [only] => only.clone(),
+            rest => {
+                let (last, init) = rest.split_last().unwrap();
+                format!("{} or {}", init.join(", "), last)
+            }
+        };
+
+        format!(
+            "Error at line {} column {}: expected {}, found {}",
+            self.line, self.column, expected, self.found
+        )
+    }
+}
+
+/// What `expect`/`expect_one_of` actually propagate via `anyhow!`/`?`: the
+/// real `expected`/`found` behind the failure, not just a rendered string.
+/// `Parser::parse`'s recovery mode downcasts a propagated `anyhow::Error`
+/// back into this so it can report what the *inner* parse attempt expected
+/// (e.g. `parse_array`'s "expected `,` or `]`") instead of falling back to
+/// `primary_start_tokens`.
+#[derive(Debug)]
+struct ExpectError {
+    expected: Vec<TokenKind>,
+    found: Token,
+}
+
+impl std::fmt::Display for ExpectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            ParseError::new(self.found.clone(), self.expected.clone()).message()
+        )
+    }
+}
+
+impl std::error::Error for ExpectError {}
+
 pub struct Parser {
     tokens: Vec<Token>,
     position: usize,
@@ -16,18 +99,75 @@ impl Parser {
         }
     }
 
-    fn current_token(&self) -> Option<&Token> {
+    fn peek(&self) -> Option<&Token> {
         self.tokens.get(self.position)
     }
 
+    /// One token past `peek()` - e.g. disambiguating `name(` from `name[`
+    /// without backtracking.
+    #[allow(dead_code)]
+    fn peek2(&self) -> Option<&Token> {
+        self.tokens.get(self.position + 1)
+    }
+
     fn advance(&mut self) {
         self.position += 1;
     }
 
+    /// Like `advance`, but returns the token it consumed.
+    fn advance_token(&mut self) -> Option<Token> {
+        let token = self.peek().cloned();
+        if token.is_some() {
+            self.advance();
+        }
+        token
+    }
+
+    /// Consumes the current token if it's `kind`, or errors (as an
+    /// `ExpectError` carrying the single-element expected set) otherwise.
+    fn expect(&mut self, kind: TokenKind) -> Result<Token> {
+        self.expect_one_of(&[kind])
+    }
+
+    /// Like `expect`, but accepts any of several alternatives - e.g. a
+    /// comma-separated list's terminator, where either a `,` (another
+    /// element) or the closing delimiter (the end of the list) is valid.
+    /// The `ExpectError` records every alternative, so `ParseError::message`
+    /// can render "expected `,` or `]`" instead of picking just one.
+    fn expect_one_of(&mut self, kinds: &[TokenKind]) -> Result<Token> {
+        match self.peek() {
+            Some(token) if kinds.contains(&token.kind) => Ok(self.advance_token().unwrap()),
+            Some(token) => Err(ExpectError {
+                expected: kinds.to_vec(),
+                found: token.clone(),
+            }
+            .into()),
+            None => Err(ExpectError {
+                expected: kinds.to_vec(),
+                found: Token {
+                    kind: TokenKind::EOF,
+                    line: 0,
+                    column: 0,
+                },
+            }
+            .into()),
+        }
+    }
+
+    /// Consumes the current token if it's `kind`, reporting whether it did -
+    /// for optional tokens like a trailing comma, where not finding it isn't
+    /// an error.
+    fn try_consume(&mut self, kind: TokenKind) -> bool {
+        if self.peek().is_some_and(|token| token.kind == kind) {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+
     fn parse_literal(&mut self) -> Result<ParsedAst> {
-        let token = self
-            .current_token()
-            .ok_or_else(|| anyhow!("Unexpected EOF"))?;
+        let token = self.peek().ok_or_else(|| anyhow!("Unexpected EOF"))?;
         let line = token.line;
         let column = token.column;
 
@@ -96,15 +236,36 @@ impl Parser {
     }
 
     fn parse_array(&mut self) -> Result<ParsedAst> {
-        let token = self
-            .current_token()
-            .ok_or_else(|| anyhow!("Unexpected EOF"))?;
+        let open = self.expect(TokenKind::Delimiter('['))?;
+        let line = open.line;
+        let column = open.column;
+
+        let mut elements = Vec::new();
+
+        while !self.try_consume(TokenKind::Delimiter(']')) {
+            elements.push(self.parse_expression(0)?);
+            let separator =
+                self.expect_one_of(&[TokenKind::Delimiter(','), TokenKind::Delimiter(']')])?;
+            if separator.kind == TokenKind::Delimiter(']') {
+                break;
+            }
+        }
+
+        Ok(ParsedAst {
+            kind: ParsedAstKind::Array(elements),
+            line,
+            column,
+        })
+    }
+
+    fn parse_tuple(&mut self) -> Result<ParsedAst> {
+        let token = self.peek().ok_or_else(|| anyhow!("Unexpected EOF"))?;
         let line = token.line;
         let column = token.column;
 
-        if token.kind != TokenKind::Delimiter('[') {
+        if token.kind != TokenKind::Delimiter('{') {
             return Err(anyhow!(
-                "Expected '[' at line {}, column {}. Found: {}",
+                "Expected '{{' at line {}, column {}. Found: {}",
                 line,
                 column,
                 token
@@ -116,29 +277,25 @@ impl Parser {
         let mut elements = Vec::new();
 
         loop {
-            let token = self
-                .current_token()
-                .ok_or_else(|| anyhow!("Unexpected EOF"))?;
+            let token = self.peek().ok_or_else(|| anyhow!("Unexpected EOF"))?;
             let line = token.line;
             let column = token.column;
 
-            if token.kind == TokenKind::Delimiter(']') {
+            if token.kind == TokenKind::Delimiter('}') {
                 self.advance();
                 break;
             }
 
-            let element = self.parse_expression()?;
+            let element = self.parse_expression(0)?;
             elements.push(element);
 
-            let token = self
-                .current_token()
-                .ok_or_else(|| anyhow!("Unexpected EOF"))?;
-            if token.kind == TokenKind::Delimiter(']') {
+            let token = self.peek().ok_or_else(|| anyhow!("Unexpected EOF"))?;
+            if token.kind == TokenKind::Delimiter('}') {
                 self.advance();
                 break;
             } else if token.kind != TokenKind::Delimiter(',') {
                 return Err(anyhow!(
-                    "Expected ',' or ']' at line {}, column {}. Found: {}",
+                    "Expected ',' or '}}' at line {}, column {}. Found: {}",
                     line,
                     column,
                     token
@@ -149,22 +306,84 @@ impl Parser {
         }
 
         Ok(ParsedAst {
-            kind: ParsedAstKind::Array(elements),
+            kind: ParsedAstKind::Tuple(elements),
             line,
             column,
         })
     }
 
     fn parse_call(&mut self) -> Result<ParsedAst> {
-        let token = self
-            .current_token()
-            .ok_or_else(|| anyhow!("Unexpected EOF"))?;
+        let open = self.expect(TokenKind::Delimiter('('))?;
+        let line = open.line;
+        let column = open.column;
+
+        let name = match self.peek() {
+            Some(Token {
+                kind: TokenKind::Identifier(name),
+                ..
+            }) => name.clone(),
+            Some(token) => {
+                return Err(anyhow!(
+                    "Expected identifier at line {}, column {}. Found: {}",
+                    token.line,
+                    token.column,
+                    token
+                ))
+            }
+            None => return Err(anyhow!("Unexpected EOF")),
+        };
+        self.advance();
+
+        let mut args = Vec::new();
+
+        while !self.try_consume(TokenKind::Delimiter(')')) {
+            args.push(self.parse_expression(0)?);
+            self.try_consume(TokenKind::Delimiter(','));
+        }
+
+        Ok(ParsedAst {
+            kind: ParsedAstKind::Call { name, args },
+            line,
+            column,
+        })
+    }
+
+    /// A unary prefix operator binds tighter than any infix operator, so its
+    /// operand is parsed with a minimum binding power higher than the
+    /// highest `infix_binding_power` right-bp (see `parse_expression`).
+    const UNARY_BP: u8 = 9;
+
+    fn is_unary_op(name: &str) -> bool {
+        matches!(name, "-" | "!")
+    }
+
+    /// `(left_bp, right_bp)` for an infix operator, or `None` if `op` isn't
+    /// one. `left_bp < right_bp` makes the operator left-associative: at
+    /// equal precedence, `parse_expression` stops folding once the next
+    /// operator's `left_bp` is below the current call's `min_bp`, so e.g.
+    /// `1 - 2 - 3` parses as `(1 - 2) - 3`.
+    fn infix_binding_power(op: &str) -> Option<(u8, u8)> {
+        Some(match op {
+            "||" | "&&" => (1, 2),
+            "==" | "!=" | "<" | ">" | "<=" | ">=" => (3, 4),
+            "+" | "-" => (5, 6),
+            "*" | "/" => (7, 8),
+            _ => return None,
+        })
+    }
+
+    /// Consumes a `{ expr; expr; ... }` block, as used by `If`/`While`
+    /// bodies: expressions are read back-to-back (no comma separators, see
+    /// `parse_tuple` for the comma-separated `{a, b, c}` literal) until the
+    /// closing `}`.
+    fn parse_block(&mut self) -> Result<ParsedAst> {
+        let token = self.peek().ok_or_else(|| anyhow!("Unexpected EOF"))?;
         let line = token.line;
         let column = token.column;
 
-        if token.kind != TokenKind::Delimiter('(') {
+        if token.kind != TokenKind::Delimiter('{') {
             return Err(anyhow!(
-                "Expected '(' at line {}, column {}. Found: {}",
+                "Expected '{{' at line {}, column {}. Found: {}",
                 line,
                 column,
                 token
@@ -173,7 +392,68 @@ impl Parser {
 
         self.advance();
 
-        let name = match self.current_token() {
+        let mut exprs = Vec::new();
+
+        loop {
+            let token = self.peek().ok_or_else(|| anyhow!("Unexpected EOF"))?;
+
+            if token.kind == TokenKind::Delimiter('}') {
+                self.advance();
+                break;
+            }
+
+            exprs.push(self.parse_expression(0)?);
+        }
+
+        Ok(ParsedAst {
+            kind: ParsedAstKind::Block(exprs),
+            line,
+            column,
+        })
+    }
+
+    /// Matches a bare (non-consuming) keyword-like identifier, e.g. `else`.
+    fn peek_is_keyword(&self, keyword: &str) -> bool {
+        matches!(
+            self.peek(),
+            Some(Token { kind: TokenKind::Identifier(id), .. })
+                if id.kind == IdentifierKind::Variable && id.name == keyword
+        )
+    }
+
+    fn parse_if(&mut self) -> Result<ParsedAst> {
+        let token = self.peek().ok_or_else(|| anyhow!("Unexpected EOF"))?;
+        let line = token.line;
+        let column = token.column;
+        self.advance(); // 'if'
+
+        let cond = self.parse_expression(0)?;
+        let then = self.parse_block()?;
+        let else_ = if self.peek_is_keyword("else") {
+            self.advance();
+            Some(Box::new(self.parse_block()?))
+        } else {
+            None
+        };
+
+        Ok(ParsedAst {
+            kind: ParsedAstKind::If {
+                cond: Box::new(cond),
+                then: Box::new(then),
+                else_,
+            },
+            line,
+            column,
+        })
+    }
+
+    fn parse_let(&mut self) -> Result<ParsedAst> {
+        let token = self.peek().ok_or_else(|| anyhow!("Unexpected EOF"))?;
+        let line = token.line;
+        let column = token.column;
+        self.advance(); // 'let'
+
+        let name = match self.peek() {
             Some(Token {
                 kind: TokenKind::Identifier(name),
                 ..
@@ -188,70 +468,585 @@ impl Parser {
             }
             None => return Err(anyhow!("Unexpected EOF")),
         };
-
         self.advance();
 
-        let mut args = Vec::new();
+        match self.peek() {
+            Some(Token {
+                kind: TokenKind::Identifier(id),
+                ..
+            }) if id.kind == IdentifierKind::Variable && id.name == "=" => {
+                self.advance();
+            }
+            Some(token) => {
+                return Err(anyhow!(
+                    "Expected '=' at line {}, column {}. Found: {}",
+                    token.line,
+                    token.column,
+                    token
+                ))
+            }
+            None => return Err(anyhow!("Unexpected EOF")),
+        }
+
+        let value = self.parse_expression(0)?;
+
+        Ok(ParsedAst {
+            kind: ParsedAstKind::Let {
+                name,
+                value: Box::new(value),
+            },
+            line,
+            column,
+        })
+    }
+
+    fn parse_while(&mut self) -> Result<ParsedAst> {
+        let token = self.peek().ok_or_else(|| anyhow!("Unexpected EOF"))?;
+        let line = token.line;
+        let column = token.column;
+        self.advance(); // 'while'
+
+        let cond = self.parse_expression(0)?;
+        let body = self.parse_block()?;
+
+        Ok(ParsedAst {
+            kind: ParsedAstKind::While {
+                cond: Box::new(cond),
+                body: Box::new(body),
+            },
+            line,
+            column,
+        })
+    }
+
+    /// A primary term: a literal/array/tuple/call, a control-flow/binding
+    /// form (`if`/`let`/`while`), or a unary prefix operator applied to one.
+    /// This is where `parse_expression`'s precedence climbing bottoms out.
+    fn parse_primary(&mut self) -> Result<ParsedAst> {
+        let token = self.peek().ok_or_else(|| anyhow!("Unexpected EOF"))?;
+        let line = token.line;
+        let column = token.column;
+
+        match &token.kind {
+            TokenKind::Delimiter('[') => self.parse_array(),
+            TokenKind::Delimiter('{') => self.parse_tuple(),
+            TokenKind::Delimiter('(') => self.parse_call(),
+            TokenKind::Identifier(id) if id.kind == IdentifierKind::Variable && id.name == "if" => {
+                self.parse_if()
+            }
+            TokenKind::Identifier(id)
+                if id.kind == IdentifierKind::Variable && id.name == "let" =>
+            {
+                self.parse_let()
+            }
+            TokenKind::Identifier(id)
+                if id.kind == IdentifierKind::Variable && id.name == "while" =>
+            {
+                self.parse_while()
+            }
+            TokenKind::Identifier(id)
+                if id.kind == IdentifierKind::Variable && Self::is_unary_op(&id.name) =>
+            {
+                let op = id.name.clone();
+                self.advance();
+                let expr = self.parse_expression(Self::UNARY_BP)?;
+                Ok(ParsedAst {
+                    kind: ParsedAstKind::Unary {
+                        op,
+                        expr: Box::new(expr),
+                    },
+                    line,
+                    column,
+                })
+            }
+            t if t.is_literal() => self.parse_literal(),
+            _ => Err(ExpectError {
+                expected: Self::primary_start_tokens(),
+                found: token.clone(),
+            }
+            .into()),
+        }
+    }
+
+    /// Wraps `parse_primary` with a postfix loop for `base[index]`/`base.field`,
+    /// consuming `[`/`.` operators until none remain so `arr[0].x` builds
+    /// `Field { base: Index { base: arr, index: 0 }, field: "x" }` - each
+    /// iteration wraps the node built so far as the new `base`.
+    fn parse_postfix(&mut self) -> Result<ParsedAst> {
+        let mut node = self.parse_primary()?;
+
+        loop {
+            match self.peek().map(|token| &token.kind) {
+                Some(TokenKind::Delimiter('[')) => {
+                    let open = self.advance_token().unwrap();
+                    let index = self.parse_expression(0)?;
+                    self.expect(TokenKind::Delimiter(']'))?;
+                    node = ParsedAst {
+                        kind: ParsedAstKind::Index {
+                            base: Box::new(node),
+                            index: Box::new(index),
+                        },
+                        line: open.line,
+                        column: open.column,
+                    };
+                }
+                Some(TokenKind::Delimiter('.')) => {
+                    let dot = self.advance_token().unwrap();
+                    let field = match self.peek() {
+                        Some(Token {
+                            kind: TokenKind::Identifier(id),
+                            ..
+                        }) => id.name.clone(),
+                        Some(token) => {
+                            return Err(anyhow!(
+                                "Expected field name at line {}, column {}. Found: {}",
+                                token.line,
+                                token.column,
+                                token
+                            ))
+                        }
+                        None => return Err(anyhow!("Unexpected EOF, expected field name")),
+                    };
+                    self.advance();
+                    node = ParsedAst {
+                        kind: ParsedAstKind::Field {
+                            base: Box::new(node),
+                            field,
+                        },
+                        line: dot.line,
+                        column: dot.column,
+                    };
+                }
+                _ => break,
+            }
+        }
+
+        Ok(node)
+    }
+
+    /// Precedence-climbing (Pratt) parser: parses a primary term, then folds
+    /// in infix operators whose `left_bp` is at least `min_bp`, recursing
+    /// with that operator's `right_bp` as the new minimum for its right-hand
+    /// side. Call with `min_bp: 0` to parse a whole expression.
+    fn parse_expression(&mut self, min_bp: u8) -> Result<ParsedAst> {
+        let mut left = self.parse_postfix()?;
 
         loop {
-            let token = self
-                .current_token()
-                .ok_or_else(|| anyhow!("Unexpected EOF"))?;
+            let op = match self.peek() {
+                Some(Token {
+                    kind: TokenKind::Identifier(id),
+                    ..
+                }) if id.kind == IdentifierKind::Variable => id.name.clone(),
+                _ => break,
+            };
+
+            let Some((left_bp, right_bp)) = Self::infix_binding_power(&op) else {
+                break;
+            };
+            if left_bp < min_bp {
+                break;
+            }
+
+            let token = self.advance_token().unwrap();
+            let line = token.line;
+            let column = token.column;
+
+            let right = self.parse_expression(right_bp)?;
+            left = ParsedAst {
+                kind: ParsedAstKind::Binary {
+                    op,
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+                line,
+                column,
+            };
+        }
+
+        Ok(left)
+    }
+
+    /// The token kinds `parse_primary` accepts at the very start of an
+    /// expression - what it reports as `expected` (via `ExpectError`) when
+    /// none of them matched. Mirrors `parse_primary`'s own match: the three
+    /// opening delimiters, every `TokenKind::is_literal` variant (including
+    /// a bare identifier, which doubles as a variable reference), and an
+    /// identifier standing for a unary prefix operator.
+    fn primary_start_tokens() -> Vec<TokenKind> {
+        vec![
+            TokenKind::Delimiter('('),
+            TokenKind::Delimiter('['),
+            TokenKind::Delimiter('{'),
+            TokenKind::Int(0),
+            TokenKind::Float(0.0),
+            TokenKind::Bool(false),
+            TokenKind::Char(' '),
+            TokenKind::String(String::new()),
+            TokenKind::Identifier(Identifier {
+                name: "identifier".to_string(),
+                kind: IdentifierKind::Variable,
+            }),
+            TokenKind::Identifier(Identifier {
+                name: "-".to_string(),
+                kind: IdentifierKind::Variable,
+            }),
+            TokenKind::Identifier(Identifier {
+                name: "!".to_string(),
+                kind: IdentifierKind::Variable,
+            }),
+        ]
+    }
+
+    /// Records a parse failure, merging its `expected` set into the
+    /// previous error instead of pushing a duplicate one when both landed
+    /// at the same `line`/`column`.
+    fn push_error(errors: &mut Vec<ParseError>, found: Token, expected: Vec<TokenKind>) {
+        if let Some(last) = errors.last_mut() {
+            if last.line == found.line && last.column == found.column {
+                last.merge(expected);
+                return;
+            }
+        }
+        errors.push(ParseError::new(found, expected));
+    }
+
+    /// Skips tokens until a synchronization point, so `parse` can resume
+    /// after a bad expression instead of giving up on the rest of the
+    /// input: a closing delimiter (consumed, since it closes whatever
+    /// unbalanced form caused the error) or `;` (also consumed, as the
+    /// boundary between statements) ends the skip; EOF just stops it.
+    fn synchronize(&mut self) {
+        while let Some(token) = self.peek() {
+            match token.kind {
+                TokenKind::EOF => break,
+                TokenKind::Delimiter(';')
+                | TokenKind::Delimiter(')')
+                | TokenKind::Delimiter(']')
+                | TokenKind::Delimiter('}') => {
+                    self.advance();
+                    return;
+                }
+                _ => self.advance(),
+            }
+        }
+    }
+
+    /// Parses every top-level expression, recovering from a bad one instead
+    /// of aborting the whole parse: a failure is recorded as a `ParseError`
+    /// positioned at the offending token and `synchronize` skips ahead to
+    /// the next boundary before resuming. A failure surfaced from *inside*
+    /// an expression (e.g. `parse_array`'s own "expected `,` or `]`", via
+    /// `expect`/`expect_one_of`) downcasts back to its real `ExpectError`
+    /// so that set is what gets recorded; only a failure with no
+    /// `ExpectError` behind it (not yet migrated off a bare `anyhow!`)
+    /// falls back to `primary_start_tokens` at the current token.
+    pub fn parse(&mut self) -> (Vec<ParsedAst>, Vec<ParseError>) {
+        let mut ast = Vec::new();
+        let mut errors = Vec::new();
+
+        while let Some(token) = self.peek() {
+            if token.kind == TokenKind::EOF {
+                break;
+            }
+
+            match self.parse_expression(0) {
+                Ok(expr) => ast.push(expr),
+                Err(err) => {
+                    match err.downcast_ref::<ExpectError>() {
+                        Some(expect_err) => {
+                            Self::push_error(
+                                &mut errors,
+                                expect_err.found.clone(),
+                                expect_err.expected.clone(),
+                            );
+                        }
+                        None => {
+                            let found = self.peek().cloned().unwrap_or(Token {
+                                kind: TokenKind::EOF,
+                                line: 0,
+                                column: 0,
+                            });
+                            Self::push_error(&mut errors, found, Self::primary_start_tokens());
+                        }
+                    }
+                    self.synchronize();
+                }
+            }
+        }
+
+        (ast, errors)
+    }
+
+    /// A type annotation: a builtin/named type, or a `*`/`[ ]` wrapper
+    /// around one (see `Type`).
+    fn parse_type(&mut self) -> Result<Type> {
+        let token = self.peek().ok_or_else(|| anyhow!("Unexpected EOF"))?;
+
+        match &token.kind {
+            TokenKind::Delimiter('[') => {
+                self.advance();
+                let element = self.parse_type()?;
+                let token = self.peek().ok_or_else(|| anyhow!("Unexpected EOF"))?;
+                if token.kind != TokenKind::Delimiter(']') {
+                    return Err(anyhow!(
+                        "Expected ']' at line {}, column {}. Found: {}",
+                        token.line,
+                        token.column,
+                        token
+                    ));
+                }
+                self.advance();
+                Ok(Type::Array(Box::new(element)))
+            }
+            TokenKind::Identifier(id) if id.kind == IdentifierKind::Variable && id.name == "*" => {
+                self.advance();
+                let pointee = self.parse_type()?;
+                Ok(Type::Pointer(Box::new(pointee)))
+            }
+            TokenKind::Identifier(id) if id.kind == IdentifierKind::Type => {
+                let name = id.name.clone();
+                self.advance();
+                Ok(Type::Named(name))
+            }
+            TokenKind::Identifier(id) if id.kind == IdentifierKind::Variable => {
+                let name = id.name.clone();
+                self.advance();
+                Ok(Type::Builtin(name))
+            }
+            _ => Err(anyhow!(
+                "Expected type at line {}, column {}. Found: {}",
+                token.line,
+                token.column,
+                token
+            )),
+        }
+    }
+
+    /// A single `name: Type` entry, shared by a function's parameter list
+    /// and a struct's field list.
+    fn parse_typed_param(&mut self) -> Result<TypedParam> {
+        let name = match self.peek() {
+            Some(Token {
+                kind: TokenKind::Identifier(name),
+                ..
+            }) => name.clone(),
+            Some(token) => {
+                return Err(anyhow!(
+                    "Expected identifier at line {}, column {}. Found: {}",
+                    token.line,
+                    token.column,
+                    token
+                ))
+            }
+            None => return Err(anyhow!("Unexpected EOF")),
+        };
+        self.advance();
+
+        let token = self.peek().ok_or_else(|| anyhow!("Unexpected EOF"))?;
+        if token.kind != TokenKind::Delimiter(':') {
+            return Err(anyhow!(
+                "Expected ':' at line {}, column {}. Found: {}",
+                token.line,
+                token.column,
+                token
+            ));
+        }
+        self.advance();
+
+        let ty = self.parse_type()?;
+
+        Ok(TypedParam { name, ty })
+    }
+
+    fn parse_function_item(&mut self) -> Result<ParsedItem> {
+        let token = self.peek().ok_or_else(|| anyhow!("Unexpected EOF"))?;
+        let line = token.line;
+        let column = token.column;
+        self.advance(); // 'fn'
+
+        let name = match self.peek() {
+            Some(Token {
+                kind: TokenKind::Identifier(name),
+                ..
+            }) => name.clone(),
+            Some(token) => {
+                return Err(anyhow!(
+                    "Expected identifier at line {}, column {}. Found: {}",
+                    token.line,
+                    token.column,
+                    token
+                ))
+            }
+            None => return Err(anyhow!("Unexpected EOF")),
+        };
+        self.advance();
 
+        let token = self.peek().ok_or_else(|| anyhow!("Unexpected EOF"))?;
+        if token.kind != TokenKind::Delimiter('(') {
+            return Err(anyhow!(
+                "Expected '(' at line {}, column {}. Found: {}",
+                token.line,
+                token.column,
+                token
+            ));
+        }
+        self.advance();
+
+        let mut params = Vec::new();
+        loop {
+            let token = self.peek().ok_or_else(|| anyhow!("Unexpected EOF"))?;
             if token.kind == TokenKind::Delimiter(')') {
                 self.advance();
                 break;
             }
 
-            let arg = self.parse_expression()?;
-            args.push(arg);
+            params.push(self.parse_typed_param()?);
 
-            let token = self
-                .current_token()
-                .ok_or_else(|| anyhow!("Unexpected EOF"))?;
-            if token.kind == TokenKind::Delimiter(',') {
+            let token = self.peek().ok_or_else(|| anyhow!("Unexpected EOF"))?;
+            if token.kind == TokenKind::Delimiter(')') {
                 self.advance();
+                break;
+            } else if token.kind != TokenKind::Delimiter(',') {
+                return Err(anyhow!(
+                    "Expected ',' or ')' at line {}, column {}. Found: {}",
+                    token.line,
+                    token.column,
+                    token
+                ));
             }
+            self.advance();
         }
 
-        Ok(ParsedAst {
-            kind: ParsedAstKind::Call { name, args },
+        let token = self.peek().ok_or_else(|| anyhow!("Unexpected EOF"))?;
+        if token.kind != TokenKind::Delimiter(':') {
+            return Err(anyhow!(
+                "Expected ':' at line {}, column {}. Found: {}",
+                token.line,
+                token.column,
+                token
+            ));
+        }
+        self.advance();
+
+        let return_type = self.parse_type()?;
+        let body = self.parse_block()?;
+
+        Ok(ParsedItem::Function {
+            name,
+            params,
+            return_type,
+            body,
             line,
             column,
         })
     }
 
-    fn parse_expression(&mut self) -> Result<ParsedAst> {
-        let token = self
-            .current_token()
-            .ok_or_else(|| anyhow!("Unexpected EOF"))?;
+    fn parse_struct_item(&mut self) -> Result<ParsedItem> {
+        let token = self.peek().ok_or_else(|| anyhow!("Unexpected EOF"))?;
         let line = token.line;
         let column = token.column;
+        self.advance(); // 'struct'
+
+        let name = match self.peek() {
+            Some(Token {
+                kind: TokenKind::Identifier(name),
+                ..
+            }) => name.clone(),
+            Some(token) => {
+                return Err(anyhow!(
+                    "Expected identifier at line {}, column {}. Found: {}",
+                    token.line,
+                    token.column,
+                    token
+                ))
+            }
+            None => return Err(anyhow!("Unexpected EOF")),
+        };
+        self.advance();
+
+        let token = self.peek().ok_or_else(|| anyhow!("Unexpected EOF"))?;
+        if token.kind != TokenKind::Delimiter('{') {
+            return Err(anyhow!(
+                "Expected '{{' at line {}, column {}. Found: {}",
+                token.line,
+                token.column,
+                token
+            ));
+        }
+        self.advance();
+
+        let mut fields = Vec::new();
+        loop {
+            let token = self.peek().ok_or_else(|| anyhow!("Unexpected EOF"))?;
+            if token.kind == TokenKind::Delimiter('}') {
+                self.advance();
+                break;
+            }
+
+            fields.push(self.parse_typed_param()?);
+
+            let token = self.peek().ok_or_else(|| anyhow!("Unexpected EOF"))?;
+            if token.kind == TokenKind::Delimiter('}') {
+                self.advance();
+                break;
+            } else if token.kind != TokenKind::Delimiter(',') {
+                return Err(anyhow!(
+                    "Expected ',' or '}}' at line {}, column {}. Found: {}",
+                    token.line,
+                    token.column,
+                    token
+                ));
+            }
+            self.advance();
+        }
+
+        Ok(ParsedItem::Struct {
+            name,
+            fields,
+            line,
+            column,
+        })
+    }
+
+    /// A top-level `fn`/`struct` declaration (see `ParsedItem`).
+    fn parse_item(&mut self) -> Result<ParsedItem> {
+        let token = self.peek().ok_or_else(|| anyhow!("Unexpected EOF"))?;
 
         match &token.kind {
-            TokenKind::Delimiter('[') => self.parse_array(),
-            TokenKind::Delimiter('(') => self.parse_call(),
-            t if t.is_literal() => self.parse_literal(),
+            TokenKind::Identifier(id) if id.kind == IdentifierKind::Variable && id.name == "fn" => {
+                self.parse_function_item()
+            }
+            TokenKind::Identifier(id)
+                if id.kind == IdentifierKind::Variable && id.name == "struct" =>
+            {
+                self.parse_struct_item()
+            }
             _ => Err(anyhow!(
-                "Expected expression at line {}, column {}. Found: {}",
-                line,
-                column,
+                "Expected 'fn' or 'struct' at line {}, column {}. Found: {}",
+                token.line,
+                token.column,
                 token
             )),
         }
     }
 
-    pub fn parse(&mut self) -> Result<Vec<ParsedAst>> {
-        let mut ast = Vec::new();
+    /// Collects top-level `fn`/`struct` declarations; expressions only
+    /// appear nested inside a declaration's body block. This is a separate
+    /// entry point from `parse` (which still reads a flat `Vec<ParsedAst>`
+    /// of bare expressions, e.g. the `@fn`/`@struct`-macro-based programs
+    /// `src/main.rs` runs today) - nothing consumes `ParsedItem` on the
+    /// interpreter side yet, so this is unreachable until that lands.
+    #[allow(dead_code)]
+    pub fn parse_items(&mut self) -> Result<Vec<ParsedItem>> {
+        let mut items = Vec::new();
 
-        while let Some(e) = self.current_token() {
-            if e.kind == TokenKind::EOF {
+        while let Some(token) = self.peek() {
+            if token.kind == TokenKind::EOF {
                 break;
             }
-            let expr = self.parse_expression()?;
-            ast.push(expr);
+            items.push(self.parse_item()?);
         }
 
-        Ok(ast)
+        Ok(items)
     }
 }
@@ -0,0 +1,97 @@
+use std::io::{self, Write};
+
+use crate::{interpreter::Interpreter, lexer::Lexer, parser::Parser, tokens::{Token, TokenKind}};
+
+/// Runs a read-eval-print loop against a single, long-lived `Interpreter`,
+/// so functions/variables/macros defined on one line stay in scope for
+/// every line after it.
+///
+/// Input is accumulated across lines rather than parsed immediately: after
+/// tokenizing the buffer so far, [`is_balanced`] checks whether every
+/// `()`/`{}`/`[]` opened has been closed. While something is still open, or
+/// the buffer ends in an infix operator, the loop prints a continuation
+/// prompt and keeps reading; only once the buffer is balanced and complete
+/// does it get parsed and handed to `interpret`.
+pub fn run() {
+    let mut interpreter = Interpreter::new();
+    let mut buffer = String::new();
+
+    loop {
+        print!("{}", if buffer.is_empty() { "> " } else { "... " });
+        if io::stdout().flush().is_err() {
+            break;
+        }
+
+        let mut line = String::new();
+        match io::stdin().read_line(&mut line) {
+            Ok(0) => break, // EOF (Ctrl-D)
+            Ok(_) => {}
+            Err(err) => {
+                println!("Error reading input: {}", err);
+                break;
+            }
+        }
+
+        buffer.push_str(&line);
+
+        let tokens = match Lexer::new(buffer.clone()).lex() {
+            Ok(tokens) => tokens,
+            Err(err) => {
+                println!("{}", err);
+                buffer.clear();
+                continue;
+            }
+        };
+
+        // A trailing infix operator (see `Parser::parse_expression`) still
+        // needs its right-hand operand from the next line.
+        let ends_in_operator = matches!(
+            tokens.last(),
+            Some(Token { kind: TokenKind::Identifier(id), .. })
+                if matches!(
+                    id.name.as_str(),
+                    "+" | "-" | "*" | "/" | "==" | "!=" | "<" | ">" | "<=" | ">=" | "&&" | "||"
+                )
+        );
+
+        if !is_balanced(&tokens) || ends_in_operator {
+            // Still waiting on a closing delimiter or an operand; keep
+            // accumulating instead of parsing a fragment.
+            continue;
+        }
+
+        let source = std::mem::take(&mut buffer);
+
+        let (ast, errors) = Parser::new(tokens).parse();
+        if !errors.is_empty() {
+            for error in &errors {
+                println!("{}", error.message());
+            }
+            continue;
+        }
+
+        // `interpret` never tears down `interpreter.scope` on error, so
+        // whatever was defined before this line stays available even if
+        // this line has problems.
+        for diagnostic in interpreter.interpret(&ast) {
+            println!("{}", diagnostic.render(&source));
+        }
+    }
+}
+
+/// True once every `(`/`{`/`[` opened in `tokens` has a matching close.
+/// Only delimiters are counted here; `run` checks for a trailing infix
+/// operator separately.
+fn is_balanced(tokens: &[Token]) -> bool {
+    let mut depth = 0i32;
+    for token in tokens {
+        if let TokenKind::Delimiter(c) = token.kind {
+            match c {
+                '(' | '{' | '[' => depth += 1,
+                ')' | '}' | ']' => depth -= 1,
+                _ => {}
+            }
+        }
+    }
+    depth <= 0
+}
@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-pub const DELIMITERS: &str = "(){}[],:;";
+pub const DELIMITERS: &str = "(){}[],:;.";
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Token {